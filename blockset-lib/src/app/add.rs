@@ -1,10 +1,11 @@
 use core::ops::Deref;
-use std::io::{self, Cursor};
+use std::io::{self, Cursor, Read};
 
 use io_trait::{DirEntry, Io, Metadata};
 use nanvm_lib::{
     common::{cast::Cast, default::default},
     js::{
+        any::Any,
         any_cast::AnyCast,
         js_object::Property,
         js_string::{new_string, JsStringRef},
@@ -16,15 +17,22 @@ use nanvm_lib::{
 
 use crate::{
     cdt::tree_add::TreeAdd,
-    common::{progress::State, status_line::StatusLine},
+    common::{
+        data_uri::to_data_uri,
+        filter::{open, Filter},
+        progress::State,
+        status_line::StatusLine,
+    },
 };
 
-use super::{invalid_input, read_to_tree, read_to_tree_file};
+use super::{invalid_input, read_to_tree, with_path};
 
 pub struct Add<'a, T: Io, S: 'a + TreeAdd, F: Fn(&'a T) -> S> {
     pub io: &'a T,
     pub storage: &'a F,
-    pub to_posix_eol: bool,
+    /// Requested filters. They are applied to text files only.
+    pub filter: Filter,
+    pub inline_limit: u64,
     pub display_new: bool,
     pub new: u64,
     pub status: StatusLine<'a, T>,
@@ -51,11 +59,11 @@ fn read_dir_recursive<I: Io>(io: &I, path: &str) -> io::Result<Vec<(String, u64)
     Ok(result)
 }
 
-fn str_to_js_string<M: Manager>(m: M, s: impl Deref<Target = str>) -> JsStringRef<M::Dealloc> {
+pub fn str_to_js_string<M: Manager>(m: M, s: impl Deref<Target = str>) -> JsStringRef<M::Dealloc> {
     new_string(m, s.encode_utf16().collect::<Vec<_>>()).to_ref()
 }
 
-fn property<M: Manager>(
+pub fn property<M: Manager>(
     m: M,
     file: impl Deref<Target = str>,
     hash: impl Deref<Target = str>,
@@ -66,33 +74,66 @@ fn property<M: Manager>(
     )
 }
 
-pub fn directory_js<M: Manager>(m: M) -> JsStringRef<M::Dealloc> {
-    let directory16 = "directory".encode_utf16().collect::<Vec<_>>();
-    new_string(m, directory16).to_ref()
+pub const DIRECTORY: &str = "directory";
+
+pub const CONTENT: &str = "content";
+
+pub const FILTER: &str = "filter";
+
+/// A directory entry is a content hash or a data URI. If `add` filtered the file, the entry is
+/// `{"content":...,"filter":[...]}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub content: String,
+    pub filter: Filter,
+}
+
+impl From<String> for Entry {
+    fn from(content: String) -> Self {
+        Entry {
+            content,
+            filter: Filter::default(),
+        }
+    }
+}
+
+pub fn entry_property<M: Manager>(
+    m: M,
+    file: impl Deref<Target = str>,
+    entry: &Entry,
+) -> Property<M::Dealloc> {
+    let content = str_to_js_string(m, entry.content.as_str()).move_to_any();
+    let value: Any<_> = if entry.filter.is_empty() {
+        content
+    } else {
+        let names = entry
+            .filter
+            .names()
+            .into_iter()
+            .map(|n| str_to_js_string(m, n).move_to_any())
+            .collect::<Vec<_>>();
+        m.new_js_object([
+            (str_to_js_string(m, CONTENT), content),
+            (str_to_js_string(m, FILTER), m.new_js_array(names)),
+        ])
+    };
+    (str_to_js_string(m, file), value)
 }
 
-fn dir_to_json<M: Manager>(
+pub fn dir_to_json<M: Manager>(
     m: M,
     list: impl ExactSizeIterator<Item = Property<M::Dealloc>>,
 ) -> io::Result<String> {
     let dir = m.new_js_object(list);
-    let property = (directory_js(m), dir);
+    let property = (str_to_js_string(m, DIRECTORY), dir);
     let block = m.new_js_object([property]);
     to_json(block).map_err(|_| invalid_input("to_json"))
 }
 
+/// File sizes only. Entries are hashes, data URIs or filter objects, so the size of the directory
+/// block is added when the block is ready.
 fn calculate_len(files: &[(String, u64)], state: &mut State) {
-    // JSON size:
-    state.total = if files.is_empty() {
-        // `{}`
-        2
-    } else {
-        // `{` +
-        // `"` + path + `":"` + 45 + `",` = path.len() + 51
-        files.iter().fold(1, |total, (path, len)| {
-            total + len + (path.len() as u64) + 51
-        })
-    };
+    state.total = files.iter().map(|(_, len)| len).sum();
 }
 
 fn normalize_path(path: &str) -> &str {
@@ -104,22 +145,37 @@ fn normalize_path(path: &str) -> &str {
 }
 
 impl<'a, T: Io, S: 'a + TreeAdd, F: Fn(&'a T) -> S> Add<'a, T, S, F> {
-    pub fn add_file(&mut self, path: &str) -> io::Result<String> {
-        read_to_tree_file(
-            self.to_posix_eol,
+    pub fn add_file(&mut self, path: &str) -> io::Result<Entry> {
+        let (f, filter) = open(self.io.open(path).map_err(with_path(path))?, self.filter)?;
+        let content = read_to_tree(
             (self.storage)(self.io),
-            self.io.open(path)?,
+            f,
             &mut self.status,
             self.display_new,
             self.p,
             &mut self.new,
-        )
+        )?;
+        Ok(Entry { content, filter })
+    }
+    fn inline_file(&mut self, path: &str) -> io::Result<Entry> {
+        let mut v = Vec::default();
+        let (mut f, filter) = open(self.io.open(path).map_err(with_path(path))?, self.filter)?;
+        f.read_to_end(&mut v)?;
+        Ok(Entry {
+            content: to_data_uri(&v),
+            filter,
+        })
     }
     fn add_files(&mut self, path: &str, files: Vec<(String, u64)>) -> io::Result<String> {
         let mut list = Vec::default();
         for (p, len) in files {
-            let hash = self.add_file((path.to_owned() + "/" + &p).as_str())?;
-            list.push(property(GLOBAL, p, hash));
+            let file = path.to_owned() + "/" + &p;
+            let entry = if len < self.inline_limit {
+                self.inline_file(&file)?
+            } else {
+                self.add_file(&file)?
+            };
+            list.push(entry_property(GLOBAL, p, &entry));
             self.p.current += len;
         }
         dir_to_json(GLOBAL, list.into_iter())
@@ -135,9 +191,8 @@ impl<'a, T: Io, S: 'a + TreeAdd, F: Fn(&'a T) -> S> Add<'a, T, S, F> {
     fn path_to_json(&mut self, path: &str) -> io::Result<String> {
         self.calculate_and_add_files(path, read_dir_recursive(self.io, path)?)
     }
-    // TODO: move it to unit tests.
-    fn check(&mut self, _cursor: &Cursor<String>) {
-        // assert!(self.p.current + cursor.position() == self.p.total);
+    fn check(&mut self, cursor: &Cursor<String>) {
+        debug_assert_eq!(self.p.current + cursor.position(), self.p.total);
     }
     fn mem_to_tree(&mut self, cursor: &mut Cursor<String>) -> io::Result<String> {
         read_to_tree(
@@ -150,14 +205,16 @@ impl<'a, T: Io, S: 'a + TreeAdd, F: Fn(&'a T) -> S> Add<'a, T, S, F> {
         )
     }
     pub fn add_dir(&mut self, path: &str) -> io::Result<String> {
-        let mut cursor = Cursor::new(self.path_to_json(path)?);
+        let json = self.path_to_json(path)?;
+        self.p.total += json.len() as u64;
+        let mut cursor = Cursor::new(json);
         let result = self.mem_to_tree(&mut cursor)?;
         self.check(&cursor);
         Ok(result)
     }
-    pub fn add_file_or_dir(&mut self, path: &str, metadata: T::Metadata) -> io::Result<String> {
+    pub fn add_file_or_dir(&mut self, path: &str, metadata: T::Metadata) -> io::Result<Entry> {
         if metadata.is_dir() {
-            self.add_dir(normalize_path(path))
+            Ok(self.add_dir(normalize_path(path))?.into())
         } else {
             self.p.total = metadata.len();
             self.add_file(path)
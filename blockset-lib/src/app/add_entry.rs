@@ -1,47 +1,67 @@
 use std::io;
 
-use io_trait::Io;
-
 use crate::{
     cdt::tree_add::TreeAdd,
-    common::{print::Print, progress::State, status_line::StatusLine},
+    common::{
+        host::Host,
+        print::Print,
+        progress::State,
+        status_line::{StatusLine, StatusMode},
+    },
 };
 
 use super::{
-    add::{posix_path, Add},
-    invalid_input, is_to_posix_eol,
+    add::{posix_path, Add, Entry},
+    add_options,
+    args::Matches,
+    output::{hash_format, print, status_mode, Json},
+    str_to_hash, usage, with_path, AddOptions,
 };
 
-fn add_file_or_dir<'a, T: Io, S: 'a + TreeAdd>(
+pub fn add_file_or_dir<'a, T: Host, S: 'a + TreeAdd>(
     io: &'a T,
     storage: &'a impl Fn(&'a T) -> S,
-    to_posix_eol: bool,
+    options: AddOptions,
     display_new: bool,
+    mode: StatusMode,
     path: String,
-) -> io::Result<String> {
+) -> io::Result<Entry> {
     let mut add = Add {
         io,
         storage,
-        to_posix_eol,
+        filter: options.filter,
+        inline_limit: options.inline_limit,
         display_new,
         new: 0,
-        status: StatusLine::new(io),
+        status: StatusLine::new(io, mode),
         p: State {
             total: 0,
             current: 0,
         },
     };
-    add.add_file_or_dir(&path, add.io.metadata(&path)?)
+    let metadata = add.io.metadata(&path).map_err(with_path(&path))?;
+    add.add_file_or_dir(&path, metadata)
 }
 
-pub fn add_entry<'a, T: Io, S: 'a + TreeAdd>(
+pub fn add_entry<'a, T: Host, S: 'a + TreeAdd>(
     io: &'a T,
-    a: &mut T::Args,
+    m: &Matches,
     storage: &'a impl Fn(&'a T) -> S,
     display_new: bool,
 ) -> io::Result<()> {
-    let path = posix_path(&a.next().ok_or(invalid_input("missing file name"))?);
-    let to_posix_eol = is_to_posix_eol(a)?;
-    let k = add_file_or_dir(io, storage, to_posix_eol, display_new, path)?;
-    io.stdout().println([k.as_str()])
+    let path = posix_path(&m.args().next().ok_or(usage("missing file name"))?);
+    let options = add_options(m)?;
+    let mode = status_mode(m)?;
+    let format = hash_format(m)?;
+    let entry = add_file_or_dir(io, storage, options, display_new, mode, path)?;
+    let k = format.format(&str_to_hash(&entry.content)?);
+    let names = entry.filter.names();
+    let mut json = Vec::from([("hash", k.as_str().into())]);
+    if !names.is_empty() {
+        json.push(("filter", names.clone().into()));
+    }
+    // stdout has only the hash, so scripts can read it.
+    print(io, m, Json::Object(json), |stdout| {
+        stdout.println([k.as_str()])
+    })
 }
@@ -0,0 +1,340 @@
+//! Command line parsing. It doesn't depend on `Io`.
+use std::io;
+
+use super::usage;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Required,
+    Optional,
+    /// Zero or more values. It can be only the last positional argument.
+    Many,
+}
+
+pub struct Positional {
+    pub name: &'static str,
+    pub arity: Arity,
+}
+
+pub const fn arg(name: &'static str) -> Positional {
+    Positional {
+        name,
+        arity: Arity::Required,
+    }
+}
+
+pub const fn optional(name: &'static str) -> Positional {
+    Positional {
+        name,
+        arity: Arity::Optional,
+    }
+}
+
+pub const fn many(name: &'static str) -> Positional {
+    Positional {
+        name,
+        arity: Arity::Many,
+    }
+}
+
+pub struct Opt {
+    pub name: &'static str,
+    /// The name of the value, `None` for a flag.
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+pub const fn flag(name: &'static str, help: &'static str) -> Opt {
+    Opt {
+        name,
+        value: None,
+        help,
+    }
+}
+
+pub const fn opt(name: &'static str, value: &'static str, help: &'static str) -> Opt {
+    Opt {
+        name,
+        value: Some(value),
+        help,
+    }
+}
+
+/// A command has either subcommands or positional arguments.
+/// Options of a command are also accepted by its subcommands.
+pub struct Command {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub args: &'static [Positional],
+    pub options: &'static [Opt],
+    pub commands: &'static [Command],
+}
+
+impl Command {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            args: &[],
+            options: &[],
+            commands: &[],
+        }
+    }
+    pub const fn args(self, args: &'static [Positional]) -> Self {
+        Self { args, ..self }
+    }
+    pub const fn options(self, options: &'static [Opt]) -> Self {
+        Self { options, ..self }
+    }
+    pub const fn commands(self, commands: &'static [Command]) -> Self {
+        Self { commands, ..self }
+    }
+}
+
+const HELP: Opt = flag("--help", "print help");
+
+const VERSION: Opt = flag("--version", "print version");
+
+#[derive(Default, Debug, PartialEq)]
+pub struct Matches {
+    /// The path of subcommands, for example `["key", "generate"]`.
+    pub command: Vec<&'static str>,
+    pub positional: Vec<String>,
+    pub options: Vec<(&'static str, Option<String>)>,
+}
+
+impl Matches {
+    pub fn args(&self) -> impl Iterator<Item = String> + '_ {
+        self.positional.iter().cloned()
+    }
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| *n == name)
+    }
+    pub fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.options
+            .iter()
+            .filter(move |(n, _)| *n == name)
+            .filter_map(|(_, v)| v.as_deref())
+    }
+    /// The last value of the option.
+    pub fn value<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        self.values(name).last()
+    }
+    pub fn subcommand(&self, i: usize) -> &str {
+        self.command.get(i).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Parsed {
+    Run(Matches),
+    Help(String),
+    Version,
+}
+
+fn command_name(path: &[&Command], suffix: &str) -> String {
+    path[1..]
+        .iter()
+        .map(|c| c.name.to_owned() + " ")
+        .collect::<String>()
+        + suffix
+}
+
+fn placeholder(name: &str) -> String {
+    "<".to_owned() + &name.replace(' ', "-") + ">"
+}
+
+fn help(path: &[&Command]) -> String {
+    let c = path[path.len() - 1];
+    let mut usage_line =
+        "Usage: ".to_owned() + &path.iter().map(|c| c.name).collect::<Vec<_>>().join(" ");
+    if !c.commands.is_empty() {
+        usage_line += " <command>";
+    }
+    for a in c.args {
+        usage_line += &match a.arity {
+            Arity::Required => " ".to_owned() + &placeholder(a.name),
+            Arity::Optional => " [".to_owned() + &placeholder(a.name) + "]",
+            Arity::Many => " [".to_owned() + &placeholder(a.name) + "...]",
+        };
+    }
+    usage_line += " [options]";
+    let mut sections = [usage_line, c.help.to_owned()].to_vec();
+    let table = |title: &str, rows: Vec<(String, &str)>| {
+        let w = rows.iter().map(|(n, _)| n.len()).max().unwrap_or_default();
+        rows.iter().fold(title.to_owned() + ":", |s, (n, h)| {
+            s + "\n  " + n + &" ".repeat(w - n.len() + 2) + h
+        })
+    };
+    if !c.commands.is_empty() {
+        let rows = c.commands.iter().map(|c| (c.name.to_owned(), c.help));
+        sections.push(table("Commands", rows.collect()));
+    }
+    let mut options = path
+        .iter()
+        .rev()
+        .flat_map(|c| c.options)
+        .map(|o| {
+            let name = o.value.map_or(o.name.to_owned(), |v| {
+                o.name.to_owned() + " " + &placeholder(v)
+            });
+            (name, o.help)
+        })
+        .collect::<Vec<_>>();
+    options.extend([HELP, VERSION].map(|o| (o.name.to_owned(), o.help)));
+    sections.push(table("Options", options));
+    sections.join("\n\n") + "\n"
+}
+
+/// Parses arguments after the program name. Options can be in any order, `--` ends options.
+pub fn parse(root: &'static Command, a: impl IntoIterator<Item = String>) -> io::Result<Parsed> {
+    let mut a = a.into_iter();
+    let mut path = [root].to_vec();
+    let mut m = Matches::default();
+    let mut options_end = false;
+    while let Some(s) = a.next() {
+        let c = path[path.len() - 1];
+        if !options_end && s.starts_with("--") {
+            if s == "--" {
+                options_end = true;
+                continue;
+            }
+            let (name, inline) = match s.split_once('=') {
+                Some((n, v)) => (n, Some(v.to_owned())),
+                None => (s.as_str(), None),
+            };
+            if name == HELP.name {
+                return Ok(Parsed::Help(help(&path)));
+            }
+            if name == VERSION.name {
+                return Ok(Parsed::Version);
+            }
+            let o = path
+                .iter()
+                .flat_map(|c| c.options)
+                .find(|o| o.name == name)
+                .ok_or(usage("unknown option"))?;
+            let value = match (o.value, inline) {
+                (Some(v), inline) => Some(
+                    inline
+                        .or_else(|| a.next())
+                        .ok_or_else(|| usage(&("missing ".to_owned() + v)))?,
+                ),
+                (None, None) => None,
+                (None, Some(_)) => return Err(usage("unexpected option value")),
+            };
+            m.options.push((o.name, value));
+        } else if !c.commands.is_empty() {
+            let sub =
+                c.commands.iter().find(|c| c.name == s).ok_or_else(|| {
+                    usage(&("unknown ".to_owned() + &command_name(&path, "command")))
+                })?;
+            m.command.push(sub.name);
+            path.push(sub);
+        } else {
+            m.positional.push(s);
+        }
+    }
+    let c = path[path.len() - 1];
+    if !c.commands.is_empty() {
+        return Err(usage(
+            &("missing ".to_owned() + &command_name(&path, "command")),
+        ));
+    }
+    for (i, p) in c.args.iter().enumerate() {
+        if p.arity == Arity::Required && m.positional.len() <= i {
+            return Err(usage(&("missing ".to_owned() + p.name)));
+        }
+    }
+    if c.args.last().map(|p| p.arity) != Some(Arity::Many) && m.positional.len() > c.args.len() {
+        return Err(usage("unexpected argument"));
+    }
+    Ok(Parsed::Run(m))
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{arg, flag, many, opt, optional, parse, Command, Matches, Parsed};
+
+    const ROOT: Command = Command::new("x", "an example").commands(&[
+        Command::new("add", "add a file")
+            .args(&[arg("file name"), optional("target")])
+            .options(&[
+                flag("--to-posix-eol", "convert line endings"),
+                opt("--inline-limit", "inline limit", "a size"),
+            ]),
+        Command::new("key", "keys").commands(&[Command::new("list", "list keys")]),
+        Command::new("verify", "verify").args(&[many("token")]),
+    ]);
+
+    fn run(a: &[&str]) -> Result<Matches, String> {
+        match parse(&ROOT, a.iter().map(|s| s.to_string())) {
+            Ok(Parsed::Run(m)) => Ok(m),
+            Ok(p) => Err(format!("{p:?}")),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let m = run(&["add", "--inline-limit", "7", "a.txt", "--to-posix-eol"]).unwrap();
+        assert_eq!(m.command, ["add"]);
+        assert_eq!(m.positional, ["a.txt"]);
+        assert_eq!(m.value("--inline-limit"), Some("7"));
+        assert!(m.flag("--to-posix-eol"));
+        let m = run(&["add", "--inline-limit=8", "--", "--a.txt", "b"]).unwrap();
+        assert_eq!(m.positional, ["--a.txt", "b"]);
+        assert_eq!(m.value("--inline-limit"), Some("8"));
+        assert!(!m.flag("--to-posix-eol"));
+        assert_eq!(run(&["key", "list"]).unwrap().subcommand(1), "list");
+        assert_eq!(run(&["verify"]).unwrap().positional.len(), 0);
+        assert_eq!(run(&["verify", "a", "b", "c"]).unwrap().positional.len(), 3);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_errors() {
+        let e = |a: &[&str]| run(a).unwrap_err();
+        assert_eq!(e(&[]), "missing command");
+        assert_eq!(e(&["x"]), "unknown command");
+        assert_eq!(e(&["key"]), "missing key command");
+        assert_eq!(e(&["key", "x"]), "unknown key command");
+        assert_eq!(e(&["add"]), "missing file name");
+        assert_eq!(e(&["add", "a", "b", "c"]), "unexpected argument");
+        assert_eq!(e(&["add", "a", "--x"]), "unknown option");
+        assert_eq!(e(&["add", "a", "--inline-limit"]), "missing inline limit");
+        assert_eq!(
+            e(&["add", "a", "--to-posix-eol=1"]),
+            "unexpected option value"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_help() {
+        assert_eq!(run(&["--version", "add"]).unwrap_err(), "Version");
+        let Ok(Parsed::Help(h)) = parse(&ROOT, ["add".to_owned(), "--help".to_owned()]) else {
+            panic!()
+        };
+        let expected = [
+            "Usage: x add <file-name> [<target>] [options]",
+            "",
+            "add a file",
+            "",
+            "Options:",
+            "  --to-posix-eol                 convert line endings",
+            "  --inline-limit <inline-limit>  a size",
+            "  --help                         print help",
+            "  --version                      print version",
+            "",
+        ];
+        assert_eq!(h, expected.join("\n"));
+        let Ok(Parsed::Help(h)) = parse(&ROOT, ["--help".to_owned()]) else {
+            panic!()
+        };
+        assert!(h.contains("Commands:\n  add     add a file\n  key     keys\n"));
+    }
+}
@@ -0,0 +1,155 @@
+use super::{
+    args::{arg, flag, many, opt, optional, Command, Opt},
+    hash::{ALSO, CHECK, SHA256},
+    output::{FORMAT, JSON, JSON_PROGRESS, PROGRESS},
+};
+
+const TO_POSIX_EOL: Opt = flag(
+    "--to-posix-eol",
+    "convert CRLF line endings of text files to LF",
+);
+
+const STRIP_BOM: Opt = flag("--strip-bom", "remove the UTF-8 BOM from text files");
+
+const INLINE_LIMIT: Opt = opt(
+    "--inline-limit",
+    "inline limit",
+    "store files smaller than the limit as data URIs",
+);
+
+const HASH_FORMAT: Opt = opt(FORMAT, "format", "base32, hex, multihash, cid or uri hash");
+
+const ADD_OPTIONS: &[Opt] = &[TO_POSIX_EOL, STRIP_BOM, INLINE_LIMIT, HASH_FORMAT];
+
+const HASH_OPTIONS: &[Opt] = &[
+    TO_POSIX_EOL,
+    STRIP_BOM,
+    INLINE_LIMIT,
+    HASH_FORMAT,
+    opt(
+        CHECK,
+        "file name",
+        "check `<hash>  <path>` or `SHA256 (<path>) = <digest>` lines of the file",
+    ),
+    opt(
+        ALSO,
+        "digest",
+        "sha256 or sha224 digests of files in the BSD format",
+    ),
+];
+
+const KEY_FILE: Opt = opt("--key", "key file", "a file with a 32-byte secret seed");
+
+pub const BLOCKSET: Command = Command::new(
+    "blockset",
+    "Stores and retrieves data blocks addressed by content-dependent tree hashes.",
+)
+.options(&[
+    flag(JSON, "print one JSON document, without progress by default"),
+    opt(
+        PROGRESS,
+        "mode",
+        "auto, terminal, plain, none or json progress on stderr",
+    ),
+    flag(JSON_PROGRESS, "the same as `--progress json`"),
+])
+.commands(&[
+    Command::new("validate", "validate a content hash").args(&[arg("hash")]),
+    Command::new(
+        "hash",
+        "calculate content hashes of files or directories, `<hash>  <path>` for many",
+    )
+    .args(&[many("file name")])
+    .options(HASH_OPTIONS),
+    Command::new(
+        "manifest",
+        "print `<hash>  <path>` lines for files of a stored directory",
+    )
+    .args(&[arg("hash")])
+    .options(&[flag(SHA256, "SHA-256 digests instead of content hashes")]),
+    Command::new("add", "add a file or a directory to the repository")
+        .args(&[arg("file name")])
+        .options(ADD_OPTIONS),
+    Command::new("get", "restore a file or a directory, `<hash>[/<path>]`")
+        .args(&[arg("hash"), arg("file name")])
+        .options(&[
+            flag(
+                "--to-native-eol",
+                "restore native line endings of files added with `--to-posix-eol`",
+            ),
+            flag(
+                "--restore-bom",
+                "restore the UTF-8 BOM of files added with `--strip-bom`",
+            ),
+        ]),
+    Command::new("cat", "write content to stdout, `<hash>[/<path>]`").args(&[arg("hash")]),
+    Command::new("commit", "create a revision block")
+        .args(&[arg("hash")])
+        .options(&[opt("--parent", "hash", "a previous revision")]),
+    Command::new("log", "print the history of a revision").args(&[arg("hash")]),
+    Command::new("merge", "merge two directory revisions with a common base").args(&[
+        arg("base"),
+        arg("ours"),
+        arg("theirs"),
+    ]),
+    Command::new("sign", "sign a data address")
+        .args(&[arg("hash")])
+        .options(&[KEY_FILE]),
+    Command::new("verify-sig", "verify a signature block")
+        .args(&[arg("hash")])
+        .options(&[opt(
+            "--ca",
+            "CA file",
+            "verify the time stamp with the TSA certificate",
+        )]),
+    Command::new("ts-request", "create an RFC 3161 time stamp request")
+        .args(&[arg("hash"), arg("file name")]),
+    Command::new(
+        "ts-attach",
+        "attach a time stamp token to a signature block",
+    )
+    .args(&[arg("hash"), arg("file name")]),
+    Command::new("tlog", "a local transparency log").commands(&[
+        Command::new("append", "append an entry").args(&[arg("hash")]),
+        Command::new("prove", "print an inclusion or a consistency proof")
+            .args(&[optional("hash")])
+            .options(&[
+                opt(
+                    "--consistency",
+                    "size",
+                    "a consistency proof from the log of the size",
+                ),
+                opt(
+                    "--index",
+                    "index",
+                    "the index of the entry, otherwise every log block is read to find it",
+                ),
+            ]),
+        Command::new("verify", "verify a proof").args(&[arg("proof"), many("proof")]),
+    ]),
+    Command::new("prove", "print a proof of a byte range").args(&[
+        arg("hash"),
+        arg("offset"),
+        arg("length"),
+    ]),
+    Command::new("verify-proof", "verify a proof of a byte range")
+        .args(&[arg("proof"), arg("excerpt")]),
+    Command::new("did", "the did:cdt0 DID method").commands(&[
+        Command::new("create", "create a DID document").options(&[KEY_FILE]),
+        Command::new("resolve", "resolve a DID").args(&[arg("DID")]),
+    ]),
+    Command::new("key", "manage signing keys").commands(&[
+        Command::new("generate", "generate a key, the seed is stored unencrypted")
+            .args(&[arg("key name")]),
+        Command::new("list", "list keys"),
+        Command::new("export", "export a key").args(&[arg("key name"), arg("file name")]),
+        Command::new("import", "import a key").args(&[arg("key name"), arg("file name")]),
+    ]),
+    Command::new("trust", "print a trust path to a public key")
+        .args(&[arg("hash")])
+        .options(&[
+            opt("--anchor", "hash", "a trusted public key"),
+            opt("--max-path", "max path", "the maximum number of signatures"),
+        ]),
+    Command::new("info", "information about the repository"),
+]);
@@ -0,0 +1,216 @@
+use std::io;
+
+use io_trait::Io;
+use nanvm_lib::{
+    js::{any::Any, any_cast::AnyCast, js_array::JsArrayRef, js_object::JsObjectRef, new::New},
+    mem::{
+        global::GLOBAL,
+        manager::{Dealloc, Manager},
+    },
+    serializer::to_json::to_json,
+};
+
+use crate::{
+    common::{
+        base32::{StrEx, ToBase32},
+        base58::{from_base58_str, to_base58},
+        print::Print,
+    },
+    ed25519::{public_key, PublicKey},
+    uint::u224::U224,
+};
+
+use super::{
+    add::{property, str_to_js_string},
+    args::Matches,
+    get::read_json_block,
+    get_property, get_string_property, invalid_input, js_string_to_string,
+    output::{object, print},
+    revision::store_json,
+    sign::read_secret_key,
+    try_move, usage,
+};
+
+/// `did:cdt0:<hash>` where `<hash>` is the address of a DID document block.
+/// The stored document has no `id` and uses relative references, `#key-1`.
+pub const DID_CDT0: &str = "did:cdt0:";
+
+const CONTEXT: &str = "@context";
+const DID_V1: &str = "https://www.w3.org/ns/did/v1";
+const MULTIKEY_V1: &str = "https://w3id.org/security/multikey/v1";
+
+const ID: &str = "id";
+const TYPE: &str = "type";
+const CONTROLLER: &str = "controller";
+const VERIFICATION_METHOD: &str = "verificationMethod";
+const MULTIKEY: &str = "Multikey";
+const PUBLIC_KEY_MULTIBASE: &str = "publicKeyMultibase";
+const AUTHENTICATION: &str = "authentication";
+const ASSERTION_METHOD: &str = "assertionMethod";
+
+const KEY_1: &str = "#key-1";
+
+/// https://www.w3.org/TR/controller-document/#Multikey
+const ED25519_PUB: [u8; 2] = [0xED, 0x01];
+
+fn to_multibase(pk: &PublicKey) -> String {
+    "z".to_owned() + &to_base58(&[ED25519_PUB.as_slice(), pk].concat())
+}
+
+fn from_multibase(s: &str) -> io::Result<PublicKey> {
+    s.strip_prefix('z')
+        .and_then(from_base58_str)
+        .and_then(|v| v.strip_prefix(&ED25519_PUB)?.try_into().ok())
+        .ok_or(invalid_input("invalid public key"))
+}
+
+pub struct DidDocument {
+    pub verification_method: Vec<(String, PublicKey)>,
+    pub authentication: Vec<String>,
+    pub assertion_method: Vec<String>,
+}
+
+impl DidDocument {
+    pub fn new(pk: &PublicKey) -> Self {
+        Self {
+            verification_method: [(KEY_1.to_owned(), *pk)].to_vec(),
+            authentication: [KEY_1.to_owned()].to_vec(),
+            assertion_method: [KEY_1.to_owned()].to_vec(),
+        }
+    }
+}
+
+/// An empty `did` creates a document for storing.
+fn document_to_json<M: Manager>(m: M, did: &str, d: &DidDocument) -> io::Result<String> {
+    let strings = |v: Vec<String>| {
+        m.new_js_array(
+            v.into_iter()
+                .map(|s| str_to_js_string(m, s).move_to_any())
+                .collect::<Vec<_>>(),
+        )
+    };
+    let references = |v: &[String]| strings(v.iter().map(|r| did.to_owned() + r).collect());
+    let methods = d
+        .verification_method
+        .iter()
+        .map(|(id, pk)| {
+            let mut p = [
+                property(m, ID, did.to_owned() + id),
+                property(m, TYPE, MULTIKEY),
+            ]
+            .to_vec();
+            if !did.is_empty() {
+                p.push(property(m, CONTROLLER, did));
+            }
+            p.push(property(m, PUBLIC_KEY_MULTIBASE, to_multibase(pk)));
+            m.new_js_object(p)
+        })
+        .collect::<Vec<_>>();
+    let mut p = [(
+        str_to_js_string(m, CONTEXT),
+        strings([DID_V1.to_owned(), MULTIKEY_V1.to_owned()].to_vec()),
+    )]
+    .to_vec();
+    if !did.is_empty() {
+        p.push(property(m, ID, did));
+    }
+    p.extend([
+        (
+            str_to_js_string(m, VERIFICATION_METHOD),
+            m.new_js_array(methods),
+        ),
+        (
+            str_to_js_string(m, AUTHENTICATION),
+            references(&d.authentication),
+        ),
+        (
+            str_to_js_string(m, ASSERTION_METHOD),
+            references(&d.assertion_method),
+        ),
+    ]);
+    to_json(m.new_js_object(p)).map_err(|_| invalid_input("to_json"))
+}
+
+fn get_array<D: Dealloc>(o: &JsObjectRef<D>, name: &str) -> io::Result<Vec<Any<D>>> {
+    Ok(try_move::<_, JsArrayRef<_>>(get_property(o, name)?)?
+        .items()
+        .to_vec())
+}
+
+fn get_strings<D: Dealloc>(o: &JsObjectRef<D>, name: &str) -> io::Result<Vec<String>> {
+    get_array(o, name)?
+        .into_iter()
+        .map(|s| js_string_to_string(&try_move(s)?))
+        .collect()
+}
+
+fn invalid_document() -> io::Error {
+    invalid_input("invalid DID document")
+}
+
+/// Reads and validates a stored DID document.
+pub fn read_document(io: &impl Io, did: &str) -> io::Result<DidDocument> {
+    // only the canonical form, so `id` and `controller` are the given DID.
+    let d = did
+        .strip_prefix(DID_CDT0)
+        .and_then(|s| s.from_base32::<U224>().filter(|d| d.to_base32() == s))
+        .ok_or(invalid_input("invalid DID"))?;
+    let o = read_json_block(io, GLOBAL, &d)?;
+    if !get_strings(&o, CONTEXT)?.iter().any(|c| c == DID_V1) || get_property(&o, ID).is_ok() {
+        return Err(invalid_document());
+    }
+    let verification_method = get_array(&o, VERIFICATION_METHOD)?
+        .into_iter()
+        .map(|v| {
+            let v = try_move::<_, JsObjectRef<_>>(v)?;
+            let id = get_string_property(&v, ID)?;
+            if !id.starts_with('#') || get_string_property(&v, TYPE)? != MULTIKEY {
+                return Err(invalid_document());
+            }
+            Ok((
+                id,
+                from_multibase(&get_string_property(&v, PUBLIC_KEY_MULTIBASE)?)?,
+            ))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    let references = |name| {
+        let r = get_strings(&o, name)?;
+        if r.iter()
+            .all(|r| verification_method.iter().any(|(id, _)| id == r))
+        {
+            Ok(r)
+        } else {
+            Err(invalid_document())
+        }
+    };
+    Ok(DidDocument {
+        authentication: references(AUTHENTICATION)?,
+        assertion_method: references(ASSERTION_METHOD)?,
+        verification_method,
+    })
+}
+
+fn create(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let sk = read_secret_key(io, m.value("--key").ok_or(usage("missing key file"))?)?;
+    let d = DidDocument::new(&public_key(&sk));
+    let did = DID_CDT0.to_owned() + &store_json(io, document_to_json(GLOBAL, "", &d)?)?;
+    print(io, m, object([("did", did.as_str().into())]), |stdout| {
+        stdout.println([did.as_str()])
+    })
+}
+
+/// The DID document is JSON in both modes.
+fn resolve(io: &impl Io, a: &mut impl Iterator<Item = String>) -> io::Result<()> {
+    let did = a.next().ok_or(usage("missing DID"))?;
+    let d = read_document(io, &did)?;
+    io.stdout()
+        .println([document_to_json(GLOBAL, &did, &d)?.as_str()])
+}
+
+pub fn did(io: &impl Io, m: &Matches) -> io::Result<()> {
+    match m.subcommand(1) {
+        "create" => create(io, m),
+        "resolve" => resolve(io, &mut m.args()),
+        _ => Err(usage("unknown did command")),
+    }
+}
@@ -3,22 +3,34 @@ use std::io::{self, Cursor, Write};
 use io_trait::Io;
 use nanvm_lib::{
     common::default::default,
-    js::{any::Any, js_object::JsObjectRef},
-    mem::{global::GLOBAL, manager::Manager},
+    js::{any::Any, js_array::JsArrayRef, js_object::JsObjectRef, js_string::JsStringRef},
+    mem::{
+        global::GLOBAL,
+        manager::{Dealloc, Manager},
+    },
     parser::{parse_with_tokens, Context, ParseError, ParseResult},
     tokenizer::tokenize,
 };
 
 use crate::{
     cdt::node_type::NodeType,
-    common::status_line::{mb, StatusLine},
+    common::{
+        data_uri::{from_data_uri, is_data_uri},
+        filter::{Filter, Unfilter, NATIVE_EOL},
+        host::Host,
+        status_line::{mb, StatusLine, StatusMode},
+    },
+    error::BlocksetError,
     forest::{file::FileForest, node_id::ForestNodeId, Forest},
     uint::u224::U224,
 };
 
 use super::{
-    add::{directory_js, posix_path},
-    get_hash, invalid_input, js_string_to_string, str_to_hash, try_move,
+    add::{posix_path, Entry, CONTENT, DIRECTORY, FILTER},
+    args::Matches,
+    get_property, get_string_property, invalid_input, js_string_to_string,
+    output::{object, print, status_mode},
+    str_to_hash, try_move, usage, with_path,
 };
 
 pub fn restore(
@@ -30,6 +42,26 @@ pub fn restore(
     FileForest(io).restore(&ForestNodeId::new(NodeType::Root, hash), w, progress)
 }
 
+/// Restores a directory entry which is either a content hash or a data URI.
+pub fn restore_entry(
+    io: &impl Io,
+    entry: &str,
+    w: &mut impl Write,
+    progress: &mut impl FnMut(u64, f64) -> io::Result<()>,
+) -> io::Result<u64> {
+    if is_data_uri(entry) {
+        let data = from_data_uri(entry)
+            .ok_or(invalid_input("invalid data URI"))?
+            .data;
+        w.write_all(&data)?;
+        let len = data.len() as u64;
+        progress(len, 1.0)?;
+        Ok(len)
+    } else {
+        restore(io, &str_to_hash(entry)?, w, progress)
+    }
+}
+
 fn tokenize_and_parse<M: Manager>(
     io: &impl Io,
     manager: M,
@@ -58,7 +90,7 @@ fn create_file_path_recursively<T: Io>(io: &T, path: &str) -> io::Result<()> {
 
 pub fn create_file_recursively<T: Io>(io: &T, path: &str) -> io::Result<T::File> {
     create_file_path_recursively(io, path)?;
-    io.create(path)
+    io.create(path).map_err(with_path(path))
 }
 
 fn set_progress(
@@ -66,44 +98,150 @@ fn set_progress(
     progress_b: u64,
     progress_p: f64,
 ) -> io::Result<()> {
-    state.set_progress(&(mb(progress_b) + ", "), progress_p)
+    state.set_progress(&(mb(progress_b) + ", "), progress_b, progress_p)
+}
+
+fn read_block(io: &impl Io, d: &U224) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::default();
+    let mut w = Cursor::new(&mut buffer);
+    restore(io, d, &mut w, &mut |_, _| Ok(()))?;
+    Ok(buffer)
 }
 
-fn get_if(d: &U224, path: &str, io: &impl Io) -> io::Result<()> {
-    let mut state = StatusLine::new(io);
+/// Parses the content `v` of the JSON block `d`.
+fn parse_json_block<M: Manager>(
+    io: &impl Io,
+    manager: M,
+    d: &U224,
+    v: Vec<u8>,
+) -> io::Result<JsObjectRef<M::Dealloc>> {
+    parse_json(io, manager, v)
+        .and_then(try_move)
+        .map_err(|e| BlocksetError::CorruptBlock(*d, e.to_string()).into())
+}
+
+pub fn read_json_block<M: Manager>(
+    io: &impl Io,
+    manager: M,
+    d: &U224,
+) -> io::Result<JsObjectRef<M::Dealloc>> {
+    parse_json_block(io, manager, d, read_block(io, d)?)
+}
+
+fn entry<D: Dealloc>(v: Any<D>) -> io::Result<Entry> {
+    if let Ok(s) = v.clone().try_move::<JsStringRef<_>>() {
+        return js_string_to_string(&s).map(Entry::from);
+    }
+    let o = try_move::<_, JsObjectRef<_>>(v)?;
+    let names = try_move::<_, JsArrayRef<_>>(get_property(&o, FILTER)?)?
+        .items()
+        .iter()
+        .map(|n| js_string_to_string(&try_move(n.clone())?))
+        .collect::<io::Result<Vec<_>>>()?;
+    Ok(Entry {
+        content: get_string_property(&o, CONTENT)?,
+        filter: Filter::from_names(names.iter().map(String::as_str))
+            .ok_or(invalid_input("unknown filter"))?,
+    })
+}
+
+/// Directory blocks may come from untrusted mirrors, so a file path can't leave the target
+/// directory.
+fn check_path(path: String) -> io::Result<String> {
+    if path
+        .split('/')
+        .any(|s| s.is_empty() || s == "." || s == ".." || s.contains('\\'))
+    {
+        return Err(invalid_input(&("invalid path: ".to_owned() + &path)));
+    }
+    Ok(path)
+}
+
+fn dir_items<D: Dealloc>(json: &JsObjectRef<D>) -> io::Result<Vec<(String, Entry)>> {
+    let dir_obj = try_move::<_, JsObjectRef<_>>(get_property(json, DIRECTORY)?)?;
+    dir_obj
+        .items()
+        .iter()
+        .map(|(k, v)| Ok((check_path(js_string_to_string(k)?)?, entry(v.clone())?)))
+        .collect()
+}
+
+/// Parses the content `v` of the directory block `d`.
+pub fn parse_dir_block(io: &impl Io, d: &U224, v: Vec<u8>) -> io::Result<Vec<(String, Entry)>> {
+    dir_items(&parse_json_block(io, GLOBAL, d, v)?)
+        .map_err(|e| BlocksetError::DirectoryFormat(*d, e.to_string()).into())
+}
+
+pub fn read_dir_block(io: &impl Io, d: &U224) -> io::Result<Vec<(String, Entry)>> {
+    parse_dir_block(io, d, read_block(io, d)?)
+}
+
+#[derive(Clone, Copy)]
+struct GetOptions {
+    mode: StatusMode,
+    /// Filters which are reverted if `add` applied them.
+    revert: Filter,
+}
+
+/// Restores a directory entry into a new file.
+fn restore_entry_file(
+    io: &impl Host,
+    revert: Filter,
+    entry: &Entry,
+    path: &str,
+    progress: &mut impl FnMut(u64, f64) -> io::Result<()>,
+) -> io::Result<u64> {
+    let f = create_file_recursively(io, path)?;
+    let w = &mut Unfilter::new(f, entry.filter.and(revert), NATIVE_EOL)?;
+    restore_entry(io, &entry.content, w, progress)
+}
+
+fn restore_files(
+    io: &impl Host,
+    o: GetOptions,
+    path: &str,
+    items: Vec<(String, Entry)>,
+) -> io::Result<()> {
+    let mut state = StatusLine::new(io, o.mode);
+    let t = items.len();
+    let mut b = 0;
+    for (offset, (file, entry)) in items.iter().enumerate() {
+        b += restore_entry_file(
+            io,
+            o.revert,
+            entry,
+            (path.to_owned() + file).as_str(),
+            &mut |progress_b, progress_p| {
+                set_progress(
+                    &mut state,
+                    b + progress_b,
+                    (offset as f64 + progress_p) / t as f64,
+                )
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn restore_file(io: &impl Host, o: GetOptions, entry: &Entry, path: &str) -> io::Result<()> {
+    let mut state = StatusLine::new(io, o.mode);
+    restore_entry_file(io, o.revert, entry, path, &mut |progress_b, progress_p| {
+        set_progress(&mut state, progress_b, progress_p)
+    })?;
+    Ok(())
+}
+
+fn get_if(d: &U224, path: &str, io: &impl Host, o: GetOptions) -> io::Result<()> {
     if path.ends_with('/') {
-        let mut buffer = Vec::default();
-        let mut w = Cursor::new(&mut buffer);
-        restore(io, d, &mut w, &mut |_, _| Ok(()))?;
-        let json = try_move::<_, JsObjectRef<_>>(parse_json(io, GLOBAL, buffer)?)?;
-        let dir = directory_js(GLOBAL);
-        let dir_json = json
-            .items()
-            .iter()
-            .find(|p| p.0.items() == dir.items())
-            .ok_or(invalid_input("directory"))?;
-        let dir_obj = try_move::<_, JsObjectRef<_>>(dir_json.1.clone())?;
-        let items = dir_obj.items();
-        let t = items.len();
-        let mut b = 0;
-        for (offset, (k, v)) in items.iter().enumerate() {
-            let file = js_string_to_string(k)?;
-            let hash = js_string_to_string(&try_move(v.clone())?)?;
-            b += restore(
-                io,
-                &str_to_hash(&hash)?,
-                &mut create_file_recursively(io, (path.to_owned() + &file).as_str())?,
-                &mut |progress_b, progress_p| {
-                    set_progress(
-                        &mut state,
-                        b + progress_b,
-                        (offset as f64 + progress_p) / t as f64,
-                    )
-                },
-            )?;
-        }
-        Ok(())
+        restore_files(io, o, path, read_dir_block(io, d)?)
     } else {
+        // only a directory entry records applied filters.
+        if !o.revert.is_empty() {
+            return Err(usage(
+                "`--to-native-eol` and `--restore-bom` need a directory entry",
+            ));
+        }
+        let mut state = StatusLine::new(io, o.mode);
         restore(
             io,
             d,
@@ -114,10 +252,93 @@ fn get_if(d: &U224, path: &str, io: &impl Io) -> io::Result<()> {
     }
 }
 
-pub fn get<T: Io>(io: &T, a: &mut T::Args) -> io::Result<()> {
-    get_if(
-        &get_hash(a)?,
-        &posix_path(a.next().ok_or(invalid_input("missing file name"))?.as_str()),
+/// Splits `<hash>/<path>` into the hash and the path inside the directory block.
+fn split_address(s: &str) -> (&str, &str) {
+    s.split_once('/').unwrap_or((s, ""))
+}
+
+fn get_address(a: &mut impl Iterator<Item = String>) -> io::Result<(U224, String)> {
+    let address = a.next().ok_or(usage("missing hash"))?;
+    let (hash, sub) = split_address(&address);
+    Ok((str_to_hash(hash)?, sub.to_owned()))
+}
+
+fn find_file<'a>(items: &'a [(String, Entry)], sub: &str) -> Option<&'a Entry> {
+    items
+        .iter()
+        .find(|(file, _)| file == sub)
+        .map(|(_, entry)| entry)
+}
+
+fn sub_dir(items: Vec<(String, Entry)>, sub: &str) -> Vec<(String, Entry)> {
+    let prefix = sub.strip_suffix('/').unwrap_or(sub).to_owned() + "/";
+    items
+        .into_iter()
+        .filter_map(|(file, entry)| file.strip_prefix(&prefix).map(|f| (f.to_owned(), entry)))
+        .collect()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit_once('/').map_or(path, |(_, f)| f)
+}
+
+fn path_not_found(sub: &str) -> io::Error {
+    BlocksetError::NotFound("path not found: ".to_owned() + sub).into()
+}
+
+fn get_path(d: &U224, sub: &str, path: &str, io: &impl Host, o: GetOptions) -> io::Result<()> {
+    let items = read_dir_block(io, d)?;
+    if let Some(entry) = find_file(&items, sub) {
+        return if path.ends_with('/') {
+            restore_file(io, o, entry, &(path.to_owned() + file_name(sub)))
+        } else {
+            restore_file(io, o, entry, path)
+        };
+    }
+    let files = sub_dir(items, sub);
+    if files.is_empty() {
+        return Err(path_not_found(sub));
+    }
+    if !path.ends_with('/') {
+        return Err(usage("a directory needs a target path which ends with `/`"));
+    }
+    restore_files(io, o, path, files)
+}
+
+pub fn get(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let (d, sub) = get_address(a)?;
+    let path = posix_path(a.next().ok_or(usage("missing file name"))?.as_str());
+    let o = GetOptions {
+        mode: status_mode(m)?,
+        revert: Filter {
+            utf8_bom: m.flag("--restore-bom"),
+            posix_eol: m.flag("--to-native-eol"),
+        },
+    };
+    if sub.is_empty() {
+        get_if(&d, &path, io, o)?;
+    } else {
+        get_path(&d, &sub, &path, io, o)?;
+    }
+    print(
         io,
+        m,
+        object([("hash", (&d).into()), ("path", path.into())]),
+        |_| Ok(()),
     )
 }
+
+pub fn cat(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let (d, sub) = get_address(a)?;
+    let w = &mut io.stdout();
+    if sub.is_empty() {
+        restore(io, &d, w, &mut |_, _| Ok(()))?;
+    } else {
+        let items = read_dir_block(io, &d)?;
+        let entry = find_file(&items, &sub).ok_or_else(|| path_not_found(&sub))?;
+        restore_entry(io, &entry.content, w, &mut |_, _| Ok(()))?;
+    }
+    Ok(())
+}
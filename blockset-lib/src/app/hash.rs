@@ -0,0 +1,292 @@
+//! `blockset hash` prints one hash, `<hash>  <path>` lines for many paths or checks such lines.
+use std::io;
+
+use io_trait::Metadata;
+
+use crate::{
+    cdt::{tree_add::TreeAdd, writer::CdtWriter},
+    common::{
+        base32::ToBase32,
+        data_uri::is_data_uri,
+        filter::open,
+        hash_format::{parse_hash, to_hex},
+        host::Host,
+        print::Print,
+        progress::State,
+        status_line::{StatusLine, StatusMode},
+    },
+    sha2::hasher::Hasher,
+    uint::{u224::U224, u256::U256},
+};
+
+use super::{
+    add::posix_path,
+    add_entry::{add_entry, add_file_or_dir},
+    add_options,
+    args::Matches,
+    get::{read_dir_block, restore_entry},
+    get_hash, invalid_input,
+    output::{hash_format, object, print, status_mode, Json, JSON},
+    read_file, read_to_tree, str_to_hash, usage, with_path, AddOptions,
+};
+
+pub const CHECK: &str = "--check";
+
+pub const ALSO: &str = "--also";
+
+pub const SHA256: &str = "--sha256";
+
+#[derive(Clone, Copy)]
+enum Sha2 {
+    Sha224,
+    Sha256,
+}
+
+impl Sha2 {
+    fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "sha224" => Some(Sha2::Sha224),
+            "sha256" => Some(Sha2::Sha256),
+            _ => None,
+        }
+    }
+    const fn name(self) -> &'static str {
+        match self {
+            Sha2::Sha224 => "sha224",
+            Sha2::Sha256 => "sha256",
+        }
+    }
+    /// The name in `SHA256 (<path>) = <digest>` lines.
+    const fn tag(self) -> &'static str {
+        match self {
+            Sha2::Sha224 => "SHA224",
+            Sha2::Sha256 => "SHA256",
+        }
+    }
+    const fn hasher(self) -> Hasher {
+        match self {
+            Sha2::Sha224 => Hasher::sha224(),
+            Sha2::Sha256 => Hasher::sha256(),
+        }
+    }
+}
+
+/// Feeds the content bytes into a SHA-2 hasher.
+struct Sha2Add(Hasher);
+
+impl TreeAdd for Sha2Add {
+    fn push(&mut self, digest: &U256, i: usize) -> io::Result<u64> {
+        if i == 0 {
+            self.0.update(&[digest[0] as u8]);
+        }
+        Ok(0)
+    }
+    fn end(&mut self, _: &U224, _: usize) -> io::Result<u64> {
+        Ok(0)
+    }
+}
+
+fn hash_file(io: &impl Host, o: AddOptions, mode: StatusMode, path: &str) -> io::Result<U224> {
+    str_to_hash(&add_file_or_dir(io, &|_| (), o, false, mode, posix_path(path))?.content)
+}
+
+/// Hashes a file once for both the content hash and the SHA-2 digest.
+fn hash_file_sha2(
+    io: &impl Host,
+    o: AddOptions,
+    mode: StatusMode,
+    path: &str,
+    sha2: Sha2,
+) -> io::Result<(U224, String)> {
+    let path = posix_path(path);
+    let metadata = io.metadata(&path).map_err(with_path(&path))?;
+    if metadata.is_dir() {
+        return Err(usage("SHA-2 digests are only for files"));
+    }
+    let (f, _) = open(io.open(&path).map_err(with_path(&path))?, o.filter)?;
+    let mut s = Sha2Add(sha2.hasher());
+    let p = State {
+        total: metadata.len(),
+        current: 0,
+    };
+    let k = read_to_tree(&mut s, f, &mut StatusLine::new(io, mode), false, p, &mut 0)?;
+    Ok((str_to_hash(&k)?, to_hex(&s.0.finish())))
+}
+
+const OK: &str = "OK";
+
+enum Expected<'a> {
+    Hash(U224),
+    Sha2(Sha2, &'a str),
+}
+
+/// `<hash>  <path>`, `<hash> *<path>` or `SHA256 (<path>) = <digest>`.
+fn parse_line(line: &str) -> Option<(Expected<'_>, &str)> {
+    // a path of `<hash>  <path>` may contain ` (` too.
+    if let Some((tag, rest)) = line.split_once(" (") {
+        if let Some(sha2) = [Sha2::Sha224, Sha2::Sha256]
+            .into_iter()
+            .find(|s| s.tag() == tag)
+        {
+            let (path, digest) = rest.rsplit_once(") = ")?;
+            return Some((Expected::Sha2(sha2, digest), path));
+        }
+    }
+    let (hash, path) = line.split_once(' ')?;
+    Some((
+        Expected::Hash(parse_hash(hash)?),
+        path.strip_prefix([' ', '*'])?,
+    ))
+}
+
+fn check(io: &impl Host, m: &Matches, sums: &str) -> io::Result<()> {
+    let o = add_options(m)?;
+    let mode = status_mode(m)?;
+    let sums =
+        String::from_utf8(read_file(io, sums)?).map_err(|_| invalid_input("invalid UTF-8"))?;
+    let mut files = Vec::default();
+    for line in sums.lines().filter(|l| !l.is_empty()) {
+        let (expected, path) = parse_line(line).ok_or(invalid_input("invalid checksum line"))?;
+        let result = match expected {
+            Expected::Hash(e) => hash_file(io, o, mode, path).map(|d| d == e),
+            Expected::Sha2(sha2, e) => {
+                hash_file_sha2(io, o, mode, path, sha2).map(|(_, d)| d.eq_ignore_ascii_case(e))
+            }
+        };
+        // a file which can't be read fails the check, like in `sha256sum`.
+        let status = match result {
+            Ok(true) => OK,
+            Ok(false) => "FAILED",
+            Err(_) => "FAILED open or read",
+        };
+        files.push((path, status));
+    }
+    let failed = files
+        .iter()
+        .filter(|(_, status)| *status != OK)
+        .map(|(path, _)| *path)
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        // the JSON error document lists failed files.
+        if m.flag(JSON) {
+            return Err(invalid_input(
+                &("checksum mismatch: ".to_owned() + &failed.join(", ")),
+            ));
+        }
+        let stdout = &mut io.stdout();
+        for (path, status) in files {
+            stdout.println([path, ": ", status])?;
+        }
+        return Err(invalid_input("checksum mismatch"));
+    }
+    let json = files
+        .iter()
+        .map(|&(path, _)| object([("path", path.into()), ("ok", true.into())]))
+        .collect::<Vec<_>>();
+    print(io, m, object([("files", json.into())]), |stdout| {
+        for (path, _) in files {
+            stdout.println([path, ": ", OK])?;
+        }
+        Ok(())
+    })
+}
+
+pub fn hash(io: &impl Host, m: &Matches) -> io::Result<()> {
+    if let Some(sums) = m.value(CHECK) {
+        return check(io, m, sums);
+    }
+    let also = match m.value(ALSO) {
+        Some(a) => Some(Sha2::from_name(a).ok_or(usage("unknown digest"))?),
+        None => None,
+    };
+    let paths = m.args().collect::<Vec<_>>();
+    if paths.len() < 2 && also.is_none() {
+        return add_entry(io, m, &|_| (), false);
+    }
+    if paths.is_empty() {
+        return Err(usage("missing file name"));
+    }
+    let o = add_options(m)?;
+    let mode = status_mode(m)?;
+    let format = hash_format(m)?;
+    let mut files = Vec::default();
+    for path in &paths {
+        let (d, digest) = match also {
+            Some(sha2) => {
+                let (d, digest) = hash_file_sha2(io, o, mode, path, sha2)?;
+                (d, Some((sha2, digest)))
+            }
+            None => (hash_file(io, o, mode, path)?, None),
+        };
+        files.push((path.as_str(), format.format(&d), digest));
+    }
+    let many = files.len() > 1;
+    let mut json = files
+        .iter()
+        .map(|(path, hash, digest)| {
+            let mut p = Vec::from([("hash", hash.as_str().into())]);
+            if many {
+                p.insert(0, ("path", (*path).into()));
+            }
+            if let Some((sha2, digest)) = digest {
+                p.push((sha2.name(), digest.as_str().into()));
+            }
+            Json::Object(p)
+        })
+        .collect::<Vec<_>>();
+    let json = if many {
+        object([("files", json.into())])
+    } else {
+        json.swap_remove(0)
+    };
+    print(io, m, json, |stdout| {
+        for (path, hash, digest) in files {
+            if many {
+                stdout.println([hash.as_str(), "  ", path])?;
+            } else {
+                stdout.println([hash.as_str()])?;
+            }
+            if let Some((sha2, digest)) = digest {
+                stdout.println([sha2.tag(), " (", path, ") = ", digest.as_str()])?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Prints `<digest>  <path>` lines for files of a stored directory.
+pub fn manifest(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let d = get_hash(&mut m.args())?;
+    let sha2 = m.flag(SHA256).then_some(Sha2::Sha256);
+    let mut files = Vec::default();
+    for (path, entry) in read_dir_block(io, &d)? {
+        let digest = if let Some(sha2) = sha2 {
+            let mut h = sha2.hasher();
+            restore_entry(io, &entry.content, &mut h, &mut |_, _| Ok(()))?;
+            to_hex(&h.finish())
+        } else if is_data_uri(&entry.content) {
+            let mut w = CdtWriter::new(());
+            restore_entry(io, &entry.content, &mut w, &mut |_, _| Ok(()))?;
+            w.finish()?.0.to_base32()
+        } else {
+            entry.content
+        };
+        files.push((path, digest));
+    }
+    let json = files
+        .iter()
+        .map(|(path, digest)| {
+            let name = sha2.map_or("hash", Sha2::name);
+            object([
+                ("path", path.as_str().into()),
+                (name, digest.as_str().into()),
+            ])
+        })
+        .collect::<Vec<_>>();
+    print(io, m, object([("files", json.into())]), |stdout| {
+        for (path, digest) in files {
+            stdout.println([digest.as_str(), "  ", path.as_str()])?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,134 @@
+use std::io;
+
+use io_trait::{DirEntry, Io};
+
+use crate::{
+    cdt::main_tree::MainTreeAdd,
+    common::{base32::ToBase32, host::Host, print::Print},
+    ed25519::{public_key, PublicKey, SecretKey},
+    uint::u224::U224,
+};
+
+use super::{
+    add::posix_path,
+    args::Matches,
+    invalid_input,
+    output::{object, print, Json},
+    revision::store_block,
+    sign::read_secret_key,
+    usage, with_path,
+};
+
+/// The keyring is next to the `cdt0` repository. Each file contains a 32-byte secret seed. Seeds
+/// are not encrypted, key files are only restricted to the owner.
+pub const KEYRING: &str = "cdt0-keys";
+
+fn key_path(name: &str) -> io::Result<String> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(invalid_input("invalid key name"));
+    }
+    Ok(KEYRING.to_owned() + "/" + name)
+}
+
+fn get_name(a: &mut impl Iterator<Item = String>) -> io::Result<String> {
+    key_path(&a.next().ok_or(usage("missing key name"))?)
+}
+
+fn get_file(a: &mut impl Iterator<Item = String>) -> io::Result<String> {
+    a.next().ok_or(usage("missing file name"))
+}
+
+/// Publishes the public key as a block so it can be resolved by its hash.
+pub fn store_public_key(io: &impl Io, sk: &SecretKey) -> io::Result<String> {
+    store_block(io, public_key(sk).to_vec())
+}
+
+/// The hash of a published public key block.
+pub fn public_key_hash(pk: &PublicKey) -> io::Result<U224> {
+    let mut tree = MainTreeAdd::new(());
+    for &c in pk {
+        tree.push(c)?;
+    }
+    Ok(tree.end()?.0)
+}
+
+/// The file is restricted before the seed is written.
+fn write_secret_key(io: &impl Host, path: &str, sk: &SecretKey) -> io::Result<()> {
+    io.write_recursively(path, &[]).map_err(with_path(path))?;
+    io.set_owner_only(path).map_err(with_path(path))?;
+    io.write(path, sk).map_err(with_path(path))
+}
+
+fn save_key(io: &impl Host, m: &Matches, path: &str, sk: &SecretKey) -> io::Result<()> {
+    if io.metadata(path).is_ok() {
+        return Err(invalid_input("key already exists"));
+    }
+    write_secret_key(io, path, sk)?;
+    let k = store_public_key(io, sk)?;
+    print(io, m, object([("key", k.as_str().into())]), |stdout| {
+        stdout.println([k.as_str()])
+    })
+}
+
+fn generate(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let path = get_name(&mut m.args())?;
+    let mut sk = SecretKey::default();
+    io.random(&mut sk)?;
+    save_key(io, m, &path, &sk)
+}
+
+fn list(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let entries = io.read_dir_type(KEYRING, false).unwrap_or_default();
+    let mut names = entries
+        .iter()
+        .map(|e| {
+            let path = posix_path(&e.path());
+            path.rsplit_once('/')
+                .map_or(path.clone(), |(_, f)| f.to_owned())
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    // other files, such as `.DS_Store`, are skipped.
+    let keys = names
+        .into_iter()
+        .filter_map(|name| {
+            let sk = read_secret_key(io, &key_path(&name).ok()?).ok()?;
+            Some(public_key_hash(&public_key(&sk)).map(|k| (k.to_base32(), name)))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    let json = keys
+        .iter()
+        .map(|(k, name)| object([("name", name.as_str().into()), ("key", k.as_str().into())]))
+        .collect();
+    print(io, m, object([("keys", Json::Array(json))]), |stdout| {
+        keys.iter()
+            .try_for_each(|(k, name)| stdout.println([name.as_str(), " ", k.as_str()]))
+    })
+}
+
+fn export(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let sk = read_secret_key(io, &get_name(a)?)?;
+    let path = get_file(a)?;
+    if io.metadata(&path).is_ok() {
+        return Err(invalid_input("file already exists"));
+    }
+    write_secret_key(io, &path, &sk)?;
+    print(io, m, object([("path", path.into())]), |_| Ok(()))
+}
+
+fn import(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let path = get_name(a)?;
+    save_key(io, m, &path, &read_secret_key(io, &get_file(a)?)?)
+}
+
+pub fn key(io: &impl Host, m: &Matches) -> io::Result<()> {
+    match m.subcommand(1) {
+        "generate" => generate(io, m),
+        "list" => list(io, m),
+        "export" => export(io, m),
+        "import" => import(io, m),
+        _ => Err(usage("unknown key command")),
+    }
+}
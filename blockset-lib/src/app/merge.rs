@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, io};
+
+use io_trait::Io;
+use nanvm_lib::mem::global::GLOBAL;
+
+use crate::{
+    cdt::writer::CdtWriter,
+    common::{base32::ToBase32, data_uri::is_data_uri, filter::Unfilter, print::Print},
+    error::BlocksetError,
+    uint::u224::U224,
+};
+
+use super::{
+    add::{dir_to_json, entry_property, Entry},
+    args::Matches,
+    get::{read_dir_block, restore_entry},
+    get_hash,
+    output::{object, print, JSON},
+    revision::{read_revision, store_json, store_revision, Revision},
+    str_to_hash,
+};
+
+pub type Dir = BTreeMap<String, Entry>;
+
+fn merge_conflict(s: String) -> io::Error {
+    BlocksetError::MergeConflict(s).into()
+}
+
+/// Merges directory entries by path. Returns the merged directory and a list of conflicting paths.
+/// Entries are equal if `content` returns the same hash of the original file content for them.
+pub fn merge_dirs(
+    base: &Dir,
+    ours: &Dir,
+    theirs: &Dir,
+    content: &mut impl FnMut(&Entry) -> io::Result<String>,
+) -> io::Result<(Dir, Vec<String>)> {
+    let mut same = |a: Option<&Entry>, b: Option<&Entry>| -> io::Result<bool> {
+        match (a, b) {
+            (Some(a), Some(b)) => Ok(a == b || content(a)? == content(b)?),
+            (a, b) => Ok(a.is_none() && b.is_none()),
+        }
+    };
+    let mut result = Dir::default();
+    let mut conflicts = Vec::default();
+    let paths = ours.keys().chain(theirs.keys()).chain(base.keys());
+    for path in paths {
+        if result.contains_key(path) || conflicts.contains(path) {
+            continue;
+        }
+        let (b, o, t) = (base.get(path), ours.get(path), theirs.get(path));
+        let merged = if same(o, t)? || same(t, b)? {
+            o
+        } else if same(o, b)? {
+            t
+        } else {
+            conflicts.push(path.clone());
+            continue;
+        };
+        if let Some(entry) = merged {
+            result.insert(path.clone(), entry.clone());
+        }
+    }
+    // a file `x` and a file `x/y` can't be restored together.
+    for path in result.keys() {
+        let dir = path.to_owned() + "/";
+        if result
+            .range(dir.clone()..)
+            .next()
+            .is_some_and(|(p, _)| p.starts_with(&dir))
+        {
+            conflicts.push(path.clone());
+        }
+    }
+    conflicts.sort();
+    Ok((result, conflicts))
+}
+
+/// The hash of the original file content of the entry.
+fn content_hash(io: &impl Io, entry: &Entry) -> io::Result<String> {
+    if entry.filter.is_empty() && !is_data_uri(&entry.content) {
+        return Ok(entry.content.clone());
+    }
+    let mut w = CdtWriter::new(());
+    // `posix-eol` is only applied to files with CRLF line endings.
+    let u = &mut Unfilter::new(&mut w, entry.filter, b"\r\n")?;
+    restore_entry(io, &entry.content, u, &mut |_, _| Ok(()))?;
+    Ok(w.finish()?.0.to_base32())
+}
+
+fn read_revision_dir(io: &impl Io, d: &U224) -> io::Result<Dir> {
+    let r = read_revision(io, d)?;
+    Ok(read_dir_block(io, &r.current)?.into_iter().collect())
+}
+
+pub fn merge(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let base = get_hash(a)?;
+    let ours = get_hash(a)?;
+    let theirs = get_hash(a)?;
+    let (dir, conflicts) = merge_dirs(
+        &read_revision_dir(io, &base)?,
+        &read_revision_dir(io, &ours)?,
+        &read_revision_dir(io, &theirs)?,
+        &mut |e| content_hash(io, e),
+    )?;
+    if !conflicts.is_empty() {
+        // the JSON error document lists conflicting paths.
+        if m.flag(JSON) {
+            return Err(merge_conflict(
+                "merge conflict: ".to_owned() + &conflicts.join(", "),
+            ));
+        }
+        let stdout = &mut io.stdout();
+        for c in conflicts {
+            stdout.println(["conflict: ", c.as_str()])?;
+        }
+        return Err(merge_conflict("merge conflict".to_owned()));
+    }
+    let json = dir_to_json(
+        GLOBAL,
+        dir.into_iter()
+            .map(|(p, e)| entry_property(GLOBAL, p, &e))
+            .collect::<Vec<_>>()
+            .into_iter(),
+    )?;
+    let current = str_to_hash(&store_json(io, json)?)?;
+    let k = store_revision(
+        io,
+        &Revision {
+            previous: [ours, theirs].to_vec(),
+            current,
+        },
+    )?;
+    print(io, m, object([("revision", k.as_str().into())]), |stdout| {
+        stdout.println([k.as_str()])
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::common::filter::Filter;
+
+    use super::{merge_dirs, Dir};
+
+    fn dir(entries: &[(&str, &str)]) -> Dir {
+        entries
+            .iter()
+            .map(|(p, e)| (p.to_string(), e.to_string().into()))
+            .collect()
+    }
+
+    /// Contents `<x>` and `u<x>` are the same.
+    fn merge(base: &Dir, ours: &Dir, theirs: &Dir) -> (Dir, Vec<String>) {
+        let content = &mut |e: &super::Entry| Ok(e.content.trim_start_matches('u').to_owned());
+        merge_dirs(base, ours, theirs, content).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let base = dir(&[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")]);
+        // `a` is changed by ours, `b` is deleted by theirs, `e` is added by both.
+        let ours = dir(&[("a", "5"), ("b", "2"), ("c", "3"), ("d", "4"), ("e", "6")]);
+        let theirs = dir(&[("a", "1"), ("c", "3"), ("d", "4"), ("e", "6"), ("f", "7")]);
+        let (r, c) = merge(&base, &ours, &theirs);
+        assert!(c.is_empty());
+        assert_eq!(
+            r,
+            dir(&[("a", "5"), ("c", "3"), ("d", "4"), ("e", "6"), ("f", "7")])
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_conflict() {
+        let base = dir(&[("a", "1"), ("b", "2"), ("c", "3")]);
+        let ours = dir(&[("a", "4"), ("c", "3"), ("d", "5")]);
+        let theirs = dir(&[("a", "6"), ("b", "7"), ("c", "3"), ("d", "8")]);
+        let (r, c) = merge(&base, &ours, &theirs);
+        assert_eq!(c, ["a", "b", "d"]);
+        assert_eq!(r, dir(&[("c", "3")]));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_same_content() {
+        let base = dir(&[("a", "1"), ("b", "2")]);
+        let ours = dir(&[("a", "u1"), ("b", "3")]);
+        let mut theirs = dir(&[("a", "1"), ("b", "u3")]);
+        theirs.get_mut("b").unwrap().filter = Filter {
+            utf8_bom: false,
+            posix_eol: true,
+        };
+        let (r, c) = merge(&base, &ours, &theirs);
+        assert!(c.is_empty());
+        assert_eq!(r, dir(&[("a", "u1"), ("b", "3")]));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_file_and_dir() {
+        let base = dir(&[("a", "1")]);
+        let ours = dir(&[("a", "1"), ("x", "2")]);
+        let theirs = dir(&[("a", "1"), ("x-y", "3"), ("x/y", "3")]);
+        let (_, c) = merge(&base, &ours, &theirs);
+        assert_eq!(c, ["x"]);
+    }
+}
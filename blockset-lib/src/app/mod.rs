@@ -1,27 +1,56 @@
-mod add;
+pub mod add;
 mod add_entry;
-mod get;
+mod args;
+mod commands;
+mod did;
+pub mod get;
+mod hash;
+mod key;
+mod merge;
+mod output;
+mod proof;
+mod revision;
+mod sign;
+mod time_stamp;
+mod tlog;
+mod trust;
 
-use std::io::{self, ErrorKind, Read, Write};
+use std::io::{self, Read};
 
 use add_entry::add_entry;
-use get::get;
+use args::{parse, Matches, Parsed};
+use commands::BLOCKSET;
+use did::did;
+use get::{cat, get};
+use hash::{hash, manifest};
+use key::key;
+use merge::merge;
+use output::{object, print, print_error, status_mode};
+use proof::{prove, verify_proof};
+use revision::{commit, log};
+use sign::{sign, verify_sig};
+use time_stamp::{ts_attach, ts_request};
+use tlog::tlog;
+use trust::trust;
 
 use io_trait::Io;
 use nanvm_lib::{
-    js::{any::Any, any_cast::AnyCast, js_string::JsStringRef},
+    js::{any::Any, any_cast::AnyCast, js_object::JsObjectRef, js_string::JsStringRef},
     mem::manager::Dealloc,
 };
 
 use crate::{
     cdt::{main_tree::MainTreeAdd, tree_add::TreeAdd},
     common::{
-        base32::{StrEx, ToBase32},
-        eol::ToPosixEol,
+        base32::ToBase32,
+        filter::Filter,
+        hash_format::parse_hash,
+        host::Host,
         print::Print,
         progress::{self, Progress, State},
         status_line::{mb, StatusLine},
     },
+    error::BlocksetError,
     forest::{file::FileForest, tree_add::ForestTreeAdd},
     info::calculate_total,
     uint::u224::U224,
@@ -45,7 +74,7 @@ fn set_progress(
     } + "Processed: "
         + &mb(current)
         + ", ";
-    state.set_progress(&s, p)
+    state.set_progress(&s, current, p)
 }
 
 fn file_read(
@@ -95,48 +124,63 @@ fn read_to_tree<T: TreeAdd>(
 }
 
 pub fn invalid_input(error: &str) -> io::Error {
-    io::Error::new(ErrorKind::InvalidInput, error)
+    BlocksetError::Invalid(error.to_owned()).into()
 }
 
-fn is_to_posix_eol(a: &mut impl Iterator<Item = String>) -> io::Result<bool> {
-    Ok(if let Some(option) = a.next() {
-        if option != "--to-posix-eol" {
-            return Err(invalid_input("unknown option"));
-        }
-        true
-    } else {
-        false
-    })
+pub fn usage(error: &str) -> io::Error {
+    BlocksetError::Usage(error.to_owned()).into()
 }
 
-fn read_to_tree_file(
-    to_posix_eol: bool,
-    s: impl TreeAdd,
-    f: impl Read + Progress,
-    state: &mut StatusLine<'_, impl Io>,
-    display_new: bool,
-    p: State,
-    new: &mut u64,
-) -> io::Result<String> {
-    if to_posix_eol {
-        read_to_tree(s, ToPosixEol::new(f), state, display_new, p, new)
-    } else {
-        read_to_tree(s, f, state, display_new, p, new)
-    }
+/// Adds the path to an error of a file operation.
+pub fn with_path(path: &str) -> impl FnOnce(io::Error) -> io::Error + '_ {
+    move |e| BlocksetError::Io(path.to_owned(), e).into()
+}
+
+fn read_file(io: &impl Io, path: &str) -> io::Result<Vec<u8>> {
+    io.read(path).map_err(with_path(path))
+}
+
+#[derive(Default, Clone, Copy)]
+struct AddOptions {
+    filter: Filter,
+    // files smaller than the limit are stored as data URIs in a directory block.
+    inline_limit: u64,
+}
+
+fn add_options(m: &Matches) -> io::Result<AddOptions> {
+    Ok(AddOptions {
+        filter: Filter {
+            utf8_bom: m.flag("--strip-bom"),
+            posix_eol: m.flag("--to-posix-eol"),
+        },
+        inline_limit: match m.value("--inline-limit") {
+            Some(v) => v.parse().map_err(|_| usage("invalid inline limit"))?,
+            None => 0,
+        },
+    })
 }
 
 fn str_to_hash(s: &str) -> io::Result<U224> {
-    s.from_base32::<U224>().ok_or(invalid_input("invalid hash"))
+    parse_hash(s).ok_or_else(|| BlocksetError::InvalidHash(s.to_owned()).into())
 }
 
 fn get_hash(a: &mut impl Iterator<Item = String>) -> io::Result<U224> {
-    let b32 = a.next().ok_or(invalid_input("missing hash"))?;
+    let b32 = a.next().ok_or(usage("missing hash"))?;
     str_to_hash(&b32)
 }
 
-fn validate(a: &mut impl Iterator<Item = String>, stdout: &mut impl Write) -> io::Result<()> {
-    let d = get_hash(a)?.to_base32();
-    stdout.println(["valid: ", d.as_str()])
+fn validate(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let d = get_hash(&mut m.args())?;
+    print(io, m, object([("hash", (&d).into())]), |stdout| {
+        stdout.println(["valid: ", d.to_base32().as_str()])
+    })
+}
+
+fn info(io: &impl Host, m: &Matches) -> io::Result<()> {
+    let size = calculate_total(io, status_mode(m)?)?;
+    print(io, m, object([("size", size.into())]), |stdout| {
+        stdout.println(["size: ", size.to_string().as_str(), " B."])
+    })
 }
 
 fn js_string_to_string(s: &JsStringRef<impl Dealloc>) -> io::Result<String> {
@@ -148,18 +192,55 @@ fn try_move<D: Dealloc, T: AnyCast<D>>(o: Any<D>) -> io::Result<T> {
     o.try_move().map_err(|_| invalid_input("invalid JSON"))
 }
 
-pub fn run(io: &impl Io) -> io::Result<()> {
+fn get_property<D: Dealloc>(o: &JsObjectRef<D>, name: &str) -> io::Result<Any<D>> {
+    let name16 = name.encode_utf16().collect::<Vec<_>>();
+    o.items()
+        .iter()
+        .find(|p| p.0.items() == name16)
+        .map(|p| p.1.clone())
+        .ok_or(invalid_input(&("missing ".to_owned() + name)))
+}
+
+fn get_string_property<D: Dealloc>(o: &JsObjectRef<D>, name: &str) -> io::Result<String> {
+    js_string_to_string(&try_move(get_property(o, name)?)?)
+}
+
+pub fn run(io: &impl Host) -> io::Result<()> {
     let stdout = &mut io.stdout();
-    let mut a = io.args();
-    a.next().unwrap();
-    let command = a.next().ok_or(invalid_input("missing command"))?;
-    match command.as_str() {
-        "validate" => validate(&mut a, stdout),
-        "hash" => add_entry(io, &mut a, &|_| (), false),
-        "add" => add_entry(io, &mut a, &|io| ForestTreeAdd::new(FileForest(io)), true),
-        "get" => get(io, &mut a),
-        "info" => stdout.println(["size: ", calculate_total(io)?.to_string().as_str(), " B."]),
-        _ => Err(invalid_input("unknown command")),
+    let m = match parse(&BLOCKSET, io.args().skip(1))? {
+        Parsed::Run(m) => m,
+        Parsed::Help(s) => return stdout.print([s.as_str()]),
+        Parsed::Version => return stdout.println(["blockset ", env!("CARGO_PKG_VERSION")]),
+    };
+    run_command(io, &m).or_else(|e| {
+        print_error(io, &m, &e)?;
+        Err(e)
+    })
+}
+
+fn run_command(io: &impl Host, m: &Matches) -> io::Result<()> {
+    match m.subcommand(0) {
+        "validate" => validate(io, m),
+        "hash" => hash(io, m),
+        "manifest" => manifest(io, m),
+        "add" => add_entry(io, m, &|io| ForestTreeAdd::new(FileForest(io)), true),
+        "get" => get(io, m),
+        "cat" => cat(io, m),
+        "commit" => commit(io, m),
+        "log" => log(io, m),
+        "merge" => merge(io, m),
+        "sign" => sign(io, m),
+        "verify-sig" => verify_sig(io, m),
+        "ts-request" => ts_request(io, m),
+        "ts-attach" => ts_attach(io, m),
+        "tlog" => tlog(io, m),
+        "prove" => prove(io, m),
+        "verify-proof" => verify_proof(io, m),
+        "did" => did(io, m),
+        "key" => key(io, m),
+        "trust" => trust(io, m),
+        "info" => info(io, m),
+        _ => Err(usage("unknown command")),
     }
 }
 
@@ -170,7 +251,24 @@ mod test {
     use std::io::Write;
     use wasm_bindgen_test::wasm_bindgen_test;
 
-    use crate::{cdt::node_id::root, common::base32::ToBase32, run, uint::u256::U256};
+    use crate::{
+        app::{
+            args::{parse, Parsed},
+            commands::BLOCKSET,
+            output::status_mode,
+        },
+        cdt::node_id::root,
+        common::{
+            base32::ToBase32,
+            filter::NATIVE_EOL,
+            hash_format::{parse_hash, HashFormat},
+            host::test::{is_owner_only, take_stderr},
+            status_line::StatusMode,
+        },
+        error::exit_code,
+        run,
+        uint::u256::U256,
+    };
 
     #[wasm_bindgen_test]
     #[test]
@@ -194,6 +292,15 @@ mod test {
         let mut io = VirtualIo::new(&["validate"]);
         let e = run(&mut io);
         assert_eq!(e.unwrap_err().to_string(), "missing hash");
+        for (args, missing) in [
+            (["merge", "a"].as_slice(), "missing ours"),
+            (&["merge", "a", "b"], "missing theirs"),
+            (&["prove", "a", "0"], "missing length"),
+            (&["verify-proof", "p"], "missing excerpt"),
+        ] {
+            let e = run(&VirtualIo::new(args)).unwrap_err();
+            assert_eq!((exit_code(&e), e.to_string().as_str()), (2, missing));
+        }
     }
 
     #[wasm_bindgen_test]
@@ -284,6 +391,7 @@ mod test {
         io.write_recursively("cdt0/roots/ab", " Hello, world!".as_bytes())
             .unwrap();
         run(&mut io).unwrap();
+        assert!(io.stdout.to_stdout().ends_with("size: 14 B.\n"));
     }
 
     #[wasm_bindgen_test]
@@ -352,10 +460,12 @@ mod test {
     #[test]
     fn test_eol() {
         add_get_expected(
-            "Hello\rworld!\r\nGoodbye!\n\r\n",
+            "Hello\rworld!\r\nGoodbye!\r\n\r\n",
             true,
             "Hello\rworld!\nGoodbye!\n\n",
         );
+        // a bare LF keeps all line endings.
+        add_get("Hello\rworld!\r\nGoodbye!\n\r\n".to_string(), true);
         add_get("Hello\rworld!\r\nGoodbye!\n\r\n".to_string(), false);
     }
 
@@ -450,4 +560,865 @@ mod test {
             run(&mut io).unwrap();
         }
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_get_path() {
+        let mut io = VirtualIo::new(&["add", "a"]);
+        io.create_dir("a").unwrap();
+        io.create_dir("a/e").unwrap();
+        io.write("a/b.txt", b"Hello world!").unwrap();
+        io.write("a/e/f.txt", b"Goodbye!").unwrap();
+        io.write("a/e/g.txt", b"Hi!").unwrap();
+        run(&io).unwrap();
+        let a = io.stdout.to_stdout()[..45].to_owned();
+        let mut get = |address: &str, path: &str| {
+            io.args = ["blockset", "get", address, path]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            run(&io)
+        };
+        // a file
+        get(&(a.clone() + "/b.txt"), "c.txt").unwrap();
+        // a file to a directory
+        get(&(a.clone() + "/e/f.txt"), "d/").unwrap();
+        // a subdirectory
+        get(&(a.clone() + "/e"), "x/").unwrap();
+        // a subdirectory to a file
+        get(&(a.clone() + "/e/"), "y.txt").unwrap_err();
+        // not found
+        get(&(a.clone() + "/e/h.txt"), "h.txt").unwrap_err();
+        assert_eq!(io.read("c.txt").unwrap(), b"Hello world!");
+        assert_eq!(io.read("d/f.txt").unwrap(), b"Goodbye!");
+        assert_eq!(io.read("x/f.txt").unwrap(), b"Goodbye!");
+        assert_eq!(io.read("x/g.txt").unwrap(), b"Hi!");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_filter() {
+        let mut io = VirtualIo::new(&[]);
+        io.create_dir("a").unwrap();
+        io.write("a/b.txt", b"\xEF\xBB\xBFHello\r\nworld!\r\n")
+            .unwrap();
+        io.write("a/c.bin", b"\xEF\xBB\xBF\r\n\0").unwrap();
+        let args = [
+            "add",
+            "a",
+            "--to-posix-eol",
+            "--strip-bom",
+            "--inline-limit",
+            "8",
+        ];
+        let a = run_out(&mut io, &args).unwrap()[..45].to_owned();
+        // the binary file is inlined as is.
+        let dir = run_out(&mut io, &["cat", &a]).unwrap();
+        assert!(dir.contains("\"b.txt\":{\"content\":\""));
+        assert!(dir.contains("\"filter\":[\"utf8-bom\",\"posix-eol\"]}"));
+        assert!(dir.contains("\"c.bin\":\"data:"));
+        let b = run_out(&mut io, &["cat", &(a.clone() + "/b.txt")]).unwrap();
+        assert_eq!(b, "Hello\nworld!\n");
+        run_out(&mut io, &["get", &a, "x/"]).unwrap();
+        assert_eq!(io.read("x/b.txt").unwrap(), b"Hello\nworld!\n");
+        assert_eq!(io.read("x/c.bin").unwrap(), b"\xEF\xBB\xBF\r\n\0");
+        let args = ["get", &a, "y/", "--restore-bom", "--to-native-eol"];
+        run_out(&mut io, &args).unwrap();
+        let expected =
+            ["\u{FEFF}Hello", "world!", ""].join(std::str::from_utf8(NATIVE_EOL).unwrap());
+        assert_eq!(io.read("y/b.txt").unwrap(), expected.as_bytes());
+        assert_eq!(io.read("y/c.bin").unwrap(), b"\xEF\xBB\xBF\r\n\0");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_filter_file() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"a\r\nb\r\n").unwrap();
+        io.write("m.txt", b"a\r\nb\n").unwrap();
+        let out = run_out(&mut io, &["add", "a.txt", "--to-posix-eol"]).unwrap();
+        let a = out[..45].to_owned();
+        assert_eq!(&out[45..], "\n");
+        let out = run_out(&mut io, &["add", "a.txt", "--to-posix-eol", "--json"]).unwrap();
+        assert_eq!(
+            out,
+            format!("{{\"hash\":\"{a}\",\"filter\":[\"posix-eol\"]}}\n")
+        );
+        // a bare hash records no filters.
+        let e = run_out(&mut io, &["get", &a, "b.txt", "--to-native-eol"]).unwrap_err();
+        assert_eq!(exit_code(&e), 2);
+        run_out(&mut io, &["get", &a, "b.txt"]).unwrap();
+        assert_eq!(io.read("b.txt").unwrap(), b"a\nb\n");
+        // a file with a bare LF is not filtered
+        let m = run_out(&mut io, &["add", "m.txt", "--to-posix-eol"]).unwrap();
+        assert_eq!(m, run_out(&mut io, &["add", "m.txt"]).unwrap());
+        run_out(&mut io, &["get", &m[..45], "n.txt"]).unwrap();
+        assert_eq!(io.read("n.txt").unwrap(), b"a\r\nb\n");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_inline() {
+        let f = |args: &[&str]| {
+            let mut io = VirtualIo::new(args);
+            io.create_dir("a").unwrap();
+            io.write("a/b.txt", b"Hello\r\nworld!").unwrap();
+            io.write("a/c.txt", &b"X".repeat(100)).unwrap();
+            run(&io).unwrap();
+            let a = io.stdout.to_stdout()[..45].to_owned();
+            io.args = ["blockset", "get", &a, "d/"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            run(&io).unwrap();
+            assert_eq!(io.read("d/c.txt").unwrap(), b"X".repeat(100));
+            (io, a)
+        };
+        let (io, a) = f(&["add", "a", "--inline-limit", "16"]);
+        assert_eq!(io.read("d/b.txt").unwrap(), b"Hello\r\nworld!");
+        let v = io
+            .read(&("cdt0/roots/".to_owned() + &a[..2] + "/" + &a[2..4] + "/" + &a[4..]))
+            .unwrap();
+        let json = String::from_utf8(v).unwrap();
+        assert!(json.contains("\"b.txt\":\"data:application/octet-stream;base64,"));
+        let (io, _) = f(&["add", "a", "--inline-limit", "16", "--to-posix-eol"]);
+        assert_eq!(io.read("d/b.txt").unwrap(), b"Hello\nworld!");
+        let (_, b) = f(&["add", "a"]);
+        assert_ne!(a, b);
+        // cat
+        let mut io = f(&["add", "a", "--inline-limit", "16"]).0;
+        let n = io.stdout.to_stdout().len();
+        io.args = ["blockset", "cat", &(a + "/b.txt")]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        run(&io).unwrap();
+        assert_eq!(&io.stdout.to_stdout()[n..], "Hello\r\nworld!");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_inline_limit_option() {
+        let io = VirtualIo::new(&["add", "a", "--inline-limit"]);
+        assert_eq!(run(&io).unwrap_err().to_string(), "missing inline limit");
+        let io = VirtualIo::new(&["add", "a", "--inline-limit", "x"]);
+        assert_eq!(run(&io).unwrap_err().to_string(), "invalid inline limit");
+    }
+
+    fn run_out(io: &mut VirtualIo, args: &[&str]) -> std::io::Result<String> {
+        io.args = ["blockset"]
+            .iter()
+            .chain(args)
+            .map(|s| s.to_string())
+            .collect();
+        let n = io.stdout.to_stdout().len();
+        run(io).map(|_| io.stdout.to_stdout()[n..].to_owned())
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_commit_log() {
+        let mut io = VirtualIo::new(&[]);
+        let mut add = |content: &str| {
+            io.write("a.txt", content.as_bytes()).unwrap();
+            run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned()
+        };
+        let a = add("Hello, world!");
+        let b = add("Goodbye, world!");
+        let r0 = run_out(&mut io, &["commit", &a]).unwrap()[..45].to_owned();
+        let r1 = run_out(&mut io, &["commit", &b, "--parent", &r0]).unwrap()[..45].to_owned();
+        let log = run_out(&mut io, &["log", &r1]).unwrap();
+        assert_eq!(log, r1.clone() + " " + &b + "\n" + &r0 + " " + &a + "\n");
+        // a parent should be a revision
+        run_out(&mut io, &["commit", &b, "--parent", &a]).unwrap_err();
+        assert_eq!(
+            run_out(&mut io, &["commit", &b, "--x"])
+                .unwrap_err()
+                .to_string(),
+            "unknown option"
+        );
+        // a merge revision
+        let r2 = run_out(&mut io, &["commit", &a, "--parent", &r1, "--parent", &r0]).unwrap()[..45]
+            .to_owned();
+        let log = run_out(&mut io, &["log", &r2]).unwrap();
+        assert_eq!(log.lines().count(), 3);
+    }
+
+    fn commit_dir(
+        io: &mut VirtualIo,
+        dir: &str,
+        files: &[(&str, &str)],
+        parents: &[&str],
+    ) -> String {
+        io.create_dir(dir).unwrap();
+        for (p, c) in files {
+            io.write(&(dir.to_owned() + "/" + p), c.as_bytes()).unwrap();
+        }
+        let d = run_out(io, &["add", dir]).unwrap()[..45].to_owned();
+        let mut args = ["commit", d.as_str()].to_vec();
+        for p in parents {
+            args.push("--parent");
+            args.push(p);
+        }
+        run_out(io, &args).unwrap()[..45].to_owned()
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_merge() {
+        let mut io = VirtualIo::new(&[]);
+        let base = commit_dir(&mut io, "base", &[("a", "1"), ("b", "2")], &[]);
+        let ours = commit_dir(&mut io, "ours", &[("a", "3"), ("b", "2")], &[&base]);
+        let theirs = commit_dir(&mut io, "theirs", &[("a", "1"), ("c", "4")], &[&base]);
+        let m = run_out(&mut io, &["merge", &base, &ours, &theirs]).unwrap()[..45].to_owned();
+        let log = run_out(&mut io, &["log", &m]).unwrap();
+        assert_eq!(log.lines().count(), 4);
+        let current = log[46..91].to_owned();
+        run_out(&mut io, &["get", &current, "m/"]).unwrap();
+        assert_eq!(io.read("m/a").unwrap(), b"3");
+        assert_eq!(io.read("m/c").unwrap(), b"4");
+        io.read("m/b").unwrap_err();
+        // conflict
+        let other = commit_dir(&mut io, "other", &[("a", "5"), ("b", "2")], &[&base]);
+        let e = run_out(&mut io, &["merge", &base, &ours, &other]).unwrap_err();
+        assert_eq!(
+            (exit_code(&e), e.to_string().as_str()),
+            (9, "merge conflict")
+        );
+        assert!(io.stdout.to_stdout().ends_with("conflict: a\n"));
+        // the same content as data URIs.
+        io.create_dir("inline").unwrap();
+        io.write("inline/a", b"1").unwrap();
+        io.write("inline/b", b"2").unwrap();
+        let d = run_out(&mut io, &["add", "inline", "--inline-limit", "16"]).unwrap();
+        let inline = run_out(&mut io, &["commit", &d[..45], "--parent", &base]).unwrap();
+        let m = run_out(&mut io, &["merge", &base, &inline[..45], &ours]).unwrap();
+        let log = run_out(&mut io, &["log", &m[..45]]).unwrap();
+        run_out(&mut io, &["get", &log[46..91], "n/"]).unwrap();
+        assert_eq!(io.read("n/a").unwrap(), b"3");
+        assert_eq!(io.read("n/b").unwrap(), b"2");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_sign() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        io.write("key", &[1; 32]).unwrap();
+        io.write("bad.key", &[1; 31]).unwrap();
+        let a = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        let s = run_out(&mut io, &["sign", &a, "--key", "key"]).unwrap()[..45].to_owned();
+        let v = run_out(&mut io, &["verify-sig", &s]).unwrap();
+        assert_eq!(v, "verified: ".to_owned() + &a + "\n");
+        let e = run_out(&mut io, &["sign", &a, "--key", "bad.key"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid key");
+        let e = run_out(&mut io, &["sign", &a]).unwrap_err();
+        assert_eq!(e.to_string(), "missing key file");
+        // a signature of another address
+        let json = String::from_utf8(
+            io.read(&("cdt0/roots/".to_owned() + &s[..2] + "/" + &s[2..4] + "/" + &s[4..]))
+                .unwrap()[1..]
+                .to_vec(),
+        )
+        .unwrap();
+        let b = run_out(&mut io, &["hash", "key"]).unwrap()[..45].to_owned();
+        io.write("s.json", json.replace(&a, &b).as_bytes()).unwrap();
+        let s = run_out(&mut io, &["add", "s.json"]).unwrap()[..45].to_owned();
+        let e = run_out(&mut io, &["verify-sig", &s]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid signature");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_time_stamp() {
+        use crate::{
+            common::base64::from_base64_str,
+            tsp::test::{ADDRESS, CA, TOKEN},
+        };
+        let mut io = VirtualIo::new(&[]);
+        io.write("key", &[1; 32]).unwrap();
+        io.write("ca.pem", CA.as_bytes()).unwrap();
+        io.write("token.der", &from_base64_str(TOKEN).unwrap())
+            .unwrap();
+        run_out(&mut io, &["ts-request", ADDRESS, "req.tsq"]).unwrap();
+        assert_eq!(io.read("req.tsq").unwrap().len(), 91);
+        let s = run_out(&mut io, &["sign", ADDRESS, "--key", "key"]).unwrap()[..45].to_owned();
+        let e = run_out(&mut io, &["verify-sig", &s, "--ca", "ca.pem"]).unwrap_err();
+        assert_eq!(e.to_string(), "missing time stamp");
+        let t = run_out(&mut io, &["ts-attach", &s, "token.der"]).unwrap()[..45].to_owned();
+        let v = run_out(&mut io, &["verify-sig", &t, "--ca", "ca.pem"]).unwrap();
+        assert_eq!(
+            v,
+            "verified: ".to_owned() + ADDRESS + "\ntime: 20261019120000Z\n"
+        );
+        // a token of another address.
+        let b = run_out(
+            &mut io,
+            &[
+                "sign",
+                "ngd7zembwj6f2tsh4gyxrcyx26h221e3f2wdgfbtq87nd",
+                "--key",
+                "key",
+            ],
+        )
+        .unwrap()[..45]
+            .to_owned();
+        let e = run_out(&mut io, &["ts-attach", &b, "token.der"]).unwrap_err();
+        assert_eq!(e.to_string(), "message imprint mismatch");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_tlog() {
+        let mut io = VirtualIo::new(&[]);
+        let last_line = |s: String| s.rsplit('\n').nth(1).unwrap().to_owned();
+        let hashes = (0..5u8)
+            .map(|i| {
+                io.write("a.txt", &[i]).unwrap();
+                run_out(&mut io, &["hash", "a.txt"]).unwrap()[..45].to_owned()
+            })
+            .collect::<Vec<_>>();
+        let heads = hashes
+            .iter()
+            .map(|h| last_line(run_out(&mut io, &["tlog", "append", h]).unwrap()))
+            .collect::<Vec<_>>();
+        assert!(heads[4].starts_with("5 "));
+        let verify = |io: &mut VirtualIo, proof: &str| {
+            let args = ["tlog", "verify"]
+                .into_iter()
+                .chain(proof.split(' '))
+                .collect::<Vec<_>>();
+            run_out(io, &args)
+        };
+        let p = last_line(run_out(&mut io, &["tlog", "prove", &hashes[2]]).unwrap());
+        assert!(p.starts_with(&("inclusion ".to_owned() + &hashes[2] + " 2 " + &heads[4])));
+        assert_eq!(verify(&mut io, &p).unwrap(), "valid\n");
+        let e = verify(&mut io, &p.replace(" 2 5 ", " 3 5 ")).unwrap_err();
+        assert_eq!(e.to_string(), "invalid proof");
+        let p = last_line(run_out(&mut io, &["tlog", "prove", "--consistency", "3"]).unwrap());
+        assert!(p.starts_with(&("consistency ".to_owned() + &heads[2] + " " + &heads[4])));
+        assert_eq!(verify(&mut io, &p).unwrap(), "valid\n");
+        let e = run_out(&mut io, &["tlog", "prove", "--consistency", "6"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid size");
+        let e = run_out(
+            &mut io,
+            &[
+                "tlog",
+                "prove",
+                "3v1d4j94scaseqgcyzr0ha5dxa9rx6ppnfbndck971ack",
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            (exit_code(&e), e.to_string().as_str()),
+            (8, "entry not found")
+        );
+        let p = run_out(&mut io, &["tlog", "prove", &hashes[2], "--index", "2"]).unwrap();
+        assert_eq!(verify(&mut io, &last_line(p)).unwrap(), "valid\n");
+        let e = run_out(&mut io, &["tlog", "prove", &hashes[2], "--index", "3"]).unwrap_err();
+        assert_eq!(e.to_string(), "entry not found");
+        // the frontier of 13 entries has three subtrees.
+        for h in &hashes {
+            run_out(&mut io, &["tlog", "append", h]).unwrap();
+        }
+        let mut heads = heads;
+        for h in &hashes[..3] {
+            heads.push(last_line(run_out(&mut io, &["tlog", "append", h]).unwrap()));
+        }
+        assert!(heads[7].starts_with("13 "));
+        for (i, h) in hashes.iter().enumerate() {
+            let p = last_line(run_out(&mut io, &["tlog", "prove", h]).unwrap());
+            assert!(p.starts_with(&format!("inclusion {h} {i} {}", heads[7])));
+            assert_eq!(verify(&mut io, &p).unwrap(), "valid\n");
+            let index = (i + 5).to_string();
+            let p = run_out(&mut io, &["tlog", "prove", h, "--index", &index]).unwrap();
+            assert_eq!(verify(&mut io, &last_line(p)).unwrap(), "valid\n");
+        }
+        for m in 1..=13 {
+            let size = m.to_string();
+            let p = run_out(&mut io, &["tlog", "prove", "--consistency", &size]).unwrap();
+            let p = last_line(p);
+            if m <= 5 {
+                assert!(p.starts_with(&("consistency ".to_owned() + &heads[m - 1])));
+            }
+            assert_eq!(verify(&mut io, &p).unwrap(), "valid\n");
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_prove() {
+        let mut io = VirtualIo::new(&[]);
+        let v = "Imagine intercepting messages from extraterrestrials.".repeat(100);
+        io.write("a.txt", v.as_bytes()).unwrap();
+        let d = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        let p = run_out(&mut io, &["prove", &d, "1000", "20"]).unwrap();
+        let p = &p[p.find(&d).unwrap()..];
+        assert!(p.starts_with(&(d.clone() + " 1000 20\n")));
+        io.write("proof.txt", p.as_bytes()).unwrap();
+        io.write("excerpt.txt", &v.as_bytes()[1000..1020]).unwrap();
+        let r = run_out(&mut io, &["verify-proof", "proof.txt", "excerpt.txt"]).unwrap();
+        assert_eq!(r, "valid: ".to_owned() + &d + " 1000 20\n");
+        // the offset is a part of the proof.
+        let tampered = p.replacen(" 1000 20", " 1001 20", 1);
+        io.write("tampered.txt", tampered.as_bytes()).unwrap();
+        let e = run_out(&mut io, &["verify-proof", "tampered.txt", "excerpt.txt"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid proof");
+        io.write("excerpt.txt", &v.as_bytes()[1001..1021]).unwrap();
+        let e = run_out(&mut io, &["verify-proof", "proof.txt", "excerpt.txt"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid proof");
+        let e = run_out(&mut io, &["prove", &d, "5300", "1"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid range");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_did() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("key", &[1; 32]).unwrap();
+        let did = run_out(&mut io, &["did", "create", "--key", "key"]).unwrap();
+        let did = &did[did.find("did:cdt0:").unwrap()..did.len() - 1];
+        assert_eq!(did.len(), 54);
+        let d = run_out(&mut io, &["did", "resolve", did]).unwrap();
+        let id = "\"".to_owned() + did + "\"";
+        assert!(d.starts_with("{\"@context\":[\"https://www.w3.org/ns/did/v1\""));
+        assert!(d.contains(&("\"id\":".to_owned() + &id)));
+        assert!(d.contains(&("\"controller\":".to_owned() + &id)));
+        assert!(d.contains(&("\"authentication\":[\"".to_owned() + did + "#key-1\"]")));
+        assert!(d.contains("\"publicKeyMultibase\":\"z6Mk"));
+        let e = run_out(&mut io, &["did", "resolve", &did[4..]]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid DID");
+        // other hash forms are not DIDs.
+        let k = parse_hash(&did[9..]).unwrap();
+        let forms = [
+            "did:cdt0:".to_owned() + &HashFormat::Hex.format(&k),
+            "did:cdt0:".to_owned() + &HashFormat::Uri.format(&k),
+            "did:cdt0:".to_owned() + &did[9..].to_uppercase(),
+        ];
+        for other in forms {
+            let e = run_out(&mut io, &["did", "resolve", &other]).unwrap_err();
+            assert_eq!(e.to_string(), "invalid DID");
+        }
+        // a reference to a missing verification method.
+        let pk = d.split('"').find(|s| s.starts_with("z6Mk")).unwrap();
+        let doc = r##"{"@context":["https://www.w3.org/ns/did/v1"],"verificationMethod":[{"id":"#key-1","type":"Multikey","publicKeyMultibase":"PK"}],"authentication":["#key-2"],"assertionMethod":[]}"##;
+        io.write("doc.json", doc.replace("PK", pk).as_bytes())
+            .unwrap();
+        let h = run_out(&mut io, &["add", "doc.json"]).unwrap()[..45].to_owned();
+        let e = run_out(&mut io, &["did", "resolve", &("did:cdt0:".to_owned() + &h)]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid DID document");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_key() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let k = run_out(&mut io, &["key", "generate", "alice"]).unwrap()[..45].to_owned();
+        assert_eq!(io.read("cdt0-keys/alice").unwrap(), [7; 32]);
+        assert!(is_owner_only("cdt0-keys/alice"));
+        // the public key is published as a block.
+        run_out(&mut io, &["get", &k, "pk"]).unwrap();
+        assert_eq!(io.read("pk").unwrap(), crate::ed25519::public_key(&[7; 32]));
+        let e = run_out(&mut io, &["key", "generate", "alice"]).unwrap_err();
+        assert_eq!(e.to_string(), "key already exists");
+        let e = run_out(&mut io, &["key", "generate", "../alice"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid key name");
+        run_out(&mut io, &["key", "export", "alice", "alice.key"]).unwrap();
+        let e = run_out(&mut io, &["key", "export", "alice", "a.txt"]).unwrap_err();
+        assert_eq!(e.to_string(), "file already exists");
+        assert_eq!(io.read("a.txt").unwrap(), b"Hello, world!");
+        run_out(&mut io, &["key", "import", "bob", "alice.key"]).unwrap();
+        assert!(is_owner_only("alice.key") && is_owner_only("cdt0-keys/bob"));
+        // listing doesn't publish keys.
+        io.write("cdt0-keys/carol", &[8; 32]).unwrap();
+        // files which are not keys are skipped.
+        io.write("cdt0-keys/.DS_Store", &[8; 32]).unwrap();
+        io.write("cdt0-keys/notes", b"x").unwrap();
+        let list = run_out(&mut io, &["key", "list"]).unwrap();
+        assert_eq!(list.lines().count(), 3);
+        let c = &list[list.find("carol ").unwrap() + 6..][..45];
+        run_out(&mut io, &["get", c, "pk"]).unwrap_err();
+        assert!(list.contains(&("bob ".to_owned() + &k + "\n")));
+        assert!(list.contains(&("alice ".to_owned() + &k + "\n")));
+        let a = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        let s = run_out(&mut io, &["sign", &a, "--key", "cdt0-keys/bob"]).unwrap()[..45].to_owned();
+        run_out(&mut io, &["verify-sig", &s]).unwrap();
+        let e = run_out(&mut io, &["key", "x"]).unwrap_err();
+        assert_eq!(e.to_string(), "unknown key command");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_trust() {
+        let mut io = VirtualIo::new(&[]);
+        let mut keys = Vec::default();
+        for (name, seed) in [("alice", 1), ("bob", 2), ("carol", 3)] {
+            io.write("k", &[seed; 32]).unwrap();
+            keys.push(run_out(&mut io, &["key", "import", name, "k"]).unwrap()[..45].to_owned());
+        }
+        let [a, b, c] = [&keys[0], &keys[1], &keys[2]];
+        let ab =
+            run_out(&mut io, &["sign", b, "--key", "cdt0-keys/alice"]).unwrap()[..45].to_owned();
+        let bc = run_out(&mut io, &["sign", c, "--key", "cdt0-keys/bob"]).unwrap()[..45].to_owned();
+        // a self-signature doesn't make a key trusted.
+        run_out(&mut io, &["sign", c, "--key", "cdt0-keys/carol"]).unwrap();
+        let e = run_out(&mut io, &["trust", c]).unwrap_err();
+        assert_eq!(e.to_string(), "untrusted key");
+        io.write("cdt0-trust", (a.to_owned() + "\n").as_bytes())
+            .unwrap();
+        let p = run_out(&mut io, &["trust", c]).unwrap();
+        let expected = [
+            a.to_owned() + " anchor",
+            b.to_owned() + " " + &ab,
+            c.to_owned() + " " + &bc,
+        ];
+        assert!(p.ends_with(&(expected.join("\n") + "\n")));
+        let p = run_out(&mut io, &["trust", a]).unwrap();
+        assert!(p.ends_with(&(a.to_owned() + " anchor\n")));
+        let e = run_out(&mut io, &["trust", c, "--max-path", "1"]).unwrap_err();
+        assert_eq!(e.to_string(), "untrusted key");
+        let p = run_out(&mut io, &["trust", c, "--max-path", "1", "--anchor", b]).unwrap();
+        assert!(p.ends_with(&(b.to_owned() + " anchor\n" + c + " " + &bc + "\n")));
+        let e = run_out(&mut io, &["trust", a, "--x"]).unwrap_err();
+        assert_eq!(e.to_string(), "unknown option");
+        // a signature block is JSON, its formatting doesn't matter.
+        run_out(&mut io, &["get", &bc, "bc.json"]).unwrap();
+        let json = String::from_utf8(io.read("bc.json").unwrap()).unwrap();
+        let json = json.replacen("{\"signature\":", "{\n  \"signature\" : ", 1);
+        io.write("bc.json", json.as_bytes()).unwrap();
+        let bc2 = run_out(&mut io, &["add", "bc.json"]).unwrap()[..45].to_owned();
+        // the original block is corrupt now.
+        let path = "cdt0/roots/".to_owned() + &bc[..2] + "/" + &bc[2..4] + "/" + &bc[4..];
+        io.write(&path, b" ").unwrap();
+        // only indexed signature blocks are read.
+        let e = run_out(&mut io, &["trust", c]).unwrap_err();
+        assert_eq!(e.to_string(), "untrusted key");
+        run_out(&mut io, &["verify-sig", &bc2]).unwrap();
+        run_out(&mut io, &["verify-sig", &bc2]).unwrap();
+        let index = String::from_utf8(io.read("cdt0-signatures").unwrap()).unwrap();
+        assert_eq!(index.lines().filter(|&l| l == bc2).count(), 1);
+        let p = run_out(&mut io, &["trust", c]).unwrap();
+        assert!(p.ends_with(&(c.to_owned() + " " + &bc2 + "\n")));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_help() {
+        let mut io = VirtualIo::new(&[]);
+        let h = run_out(&mut io, &["--help"]).unwrap();
+        assert!(h.starts_with("Usage: blockset <command> [options]\n"));
+        assert!(h.contains("\n  key           manage signing keys\n"));
+        let h = run_out(&mut io, &["key", "export", "--help"]).unwrap();
+        assert!(h.starts_with("Usage: blockset key export <key-name> <file-name> [options]\n"));
+        let v = run_out(&mut io, &["sign", "--version"]).unwrap();
+        assert_eq!(v, "blockset ".to_owned() + env!("CARGO_PKG_VERSION") + "\n");
+        // options in any order.
+        io.write("a.txt", b"Hello\r\n").unwrap();
+        let a = run_out(&mut io, &["hash", "--to-posix-eol", "a.txt"]).unwrap();
+        io.write("b.txt", b"Hello\n").unwrap();
+        assert_eq!(a, run_out(&mut io, &["hash", "--", "b.txt"]).unwrap());
+        let e = run_out(&mut io, &["validate", "a", "b"]).unwrap_err();
+        assert_eq!(e.to_string(), "unexpected argument");
+        run_out(&mut io, &["info", "--progress", "plain"]).unwrap();
+        let e = run_out(&mut io, &["info", "--progress", "x"]).unwrap_err();
+        assert_eq!(e.to_string(), "unknown progress mode");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_json() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let h = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        let j = run_out(&mut io, &["hash", "--json", "a.txt"]).unwrap();
+        assert_eq!(j, "{\"hash\":\"".to_owned() + &h + "\"}\n");
+        let j = run_out(&mut io, &["--json", "validate", &h]).unwrap();
+        assert_eq!(j, "{\"hash\":\"".to_owned() + &h + "\"}\n");
+        let j = run_out(&mut io, &["info", "--json"]).unwrap();
+        assert_eq!(j, "{\"size\":14}\n");
+        let j = run_out(&mut io, &["get", "--json", &h, "b.txt"]).unwrap();
+        assert_eq!(
+            j,
+            "{\"hash\":\"".to_owned() + &h + "\",\"path\":\"b.txt\"}\n"
+        );
+        let j = run_out(&mut io, &["key", "list", "--json"]).unwrap();
+        assert_eq!(j, "{\"keys\":[]}\n");
+        // an error is a JSON document too.
+        let n = io.stdout.to_stdout().len();
+        let e = run_out(&mut io, &["validate", "--json", "0"]).unwrap_err();
+        assert_eq!(exit_code(&e), 3);
+        assert_eq!(
+            io.stdout.to_stdout()[n..],
+            *"{\"error\":\"invalid hash\",\"code\":3}\n"
+        );
+        // progress goes to stderr of the host.
+        io.write("big.txt", &[b'x'; 300_000]).unwrap();
+        take_stderr();
+        let n = io.stdout.to_stdout().len();
+        run_out(&mut io, &["hash", "--json-progress", "big.txt"]).unwrap();
+        let e = take_stderr();
+        assert!(e.starts_with("{\"event\":\"progress\","));
+        assert!(e.ends_with("{\"event\":\"end\"}\n"));
+        assert_eq!(io.stdout.to_stdout()[n..].len(), 46);
+        run_out(&mut io, &["hash", "--progress", "none", "big.txt"]).unwrap();
+        assert_eq!(take_stderr(), "");
+        // no progress with `--json` unless it's requested.
+        let mode = |a: &[&str]| match parse(&BLOCKSET, a.iter().map(|s| s.to_string())) {
+            Ok(Parsed::Run(m)) => status_mode(&m).unwrap(),
+            _ => panic!(),
+        };
+        assert_eq!(mode(&["hash", "--json", "a.txt"]), StatusMode::Silent);
+        assert_eq!(
+            mode(&["hash", "--json", "--progress", "plain", "a.txt"]),
+            StatusMode::Plain
+        );
+        assert_eq!(
+            mode(&["hash", "--json", "--json-progress", "a.txt"]),
+            StatusMode::Json
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_exit_code() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let code = |io: &mut VirtualIo, args: &[&str]| exit_code(&run_out(io, args).unwrap_err());
+        assert_eq!(code(&mut io, &["x"]), 2);
+        assert_eq!(code(&mut io, &["cat", "0"]), 3);
+        let h = run_out(&mut io, &["hash", "a.txt"]).unwrap()[..45].to_owned();
+        assert_eq!(code(&mut io, &["cat", &h]), 4);
+        run_out(&mut io, &["add", "a.txt"]).unwrap();
+        let e = run_out(&mut io, &["cat", &(h.clone() + "/b.txt")]).unwrap_err();
+        assert_eq!(exit_code(&e), 5);
+        assert!(e
+            .to_string()
+            .starts_with(&("corrupt block ".to_owned() + &h)));
+        io.write("d.json", b"{\"files\":{}}").unwrap();
+        let d = run_out(&mut io, &["add", "d.json"]).unwrap()[..45].to_owned();
+        assert_eq!(code(&mut io, &["cat", &(d + "/b.txt")]), 6);
+        let e = run_out(&mut io, &["add", "b.txt"]).unwrap_err();
+        assert_eq!(exit_code(&e), 7);
+        assert!(e.to_string().starts_with("b.txt: "));
+        let e = run_out(&mut io, &["sign", &h, "--key", "a.txt"]).unwrap_err();
+        assert_eq!((exit_code(&e), e.to_string().as_str()), (1, "invalid key"));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_format() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let h = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        for format in ["base32", "hex", "multihash", "cid", "uri"] {
+            let f = run_out(&mut io, &["hash", "a.txt", "--format", format]).unwrap();
+            let f = f.trim_end();
+            assert_eq!(
+                run_out(&mut io, &["add", "a.txt", "--format", format])
+                    .unwrap()
+                    .trim_end(),
+                f
+            );
+            let valid = run_out(&mut io, &["validate", f]).unwrap();
+            assert_eq!(valid, "valid: ".to_owned() + &h + "\n");
+            assert_eq!(run_out(&mut io, &["cat", f]).unwrap(), "Hello, world!");
+        }
+        let uri = run_out(&mut io, &["hash", "a.txt", "--format", "uri", "--json"]).unwrap();
+        assert_eq!(uri, format!("{{\"hash\":\"cdt0:{h}\"}}\n"));
+        let e = run_out(&mut io, &["hash", "a.txt", "--format", "x"]).unwrap_err();
+        assert_eq!(e.to_string(), "unknown hash format");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_hash_check() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        io.write("b.txt", b"Hello").unwrap();
+        let a = run_out(&mut io, &["hash", "a.txt"]).unwrap()[..45].to_owned();
+        let b = run_out(&mut io, &["hash", "b.txt"]).unwrap()[..45].to_owned();
+        let sums = run_out(&mut io, &["hash", "a.txt", "b.txt"]).unwrap();
+        assert_eq!(sums, format!("{a}  a.txt\n{b}  b.txt\n"));
+        io.write("SUMS", sums.as_bytes()).unwrap();
+        let out = run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap();
+        assert_eq!(out, "a.txt: OK\nb.txt: OK\n");
+        let j = run_out(&mut io, &["hash", "--check", "SUMS", "--json"]).unwrap();
+        assert_eq!(
+            j,
+            "{\"files\":[{\"path\":\"a.txt\",\"ok\":true},{\"path\":\"b.txt\",\"ok\":true}]}\n"
+        );
+        // other hash forms and `*` of binary mode.
+        let hex = run_out(&mut io, &["hash", "a.txt", "--format", "hex"]).unwrap();
+        io.write("SUMS", format!("{} *a.txt\n", hex.trim_end()).as_bytes())
+            .unwrap();
+        assert_eq!(
+            run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap(),
+            "a.txt: OK\n"
+        );
+        io.write("b.txt", b"Hello!").unwrap();
+        io.write("SUMS", sums.as_bytes()).unwrap();
+        let n = io.stdout.to_stdout().len();
+        let e = run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap_err();
+        assert_eq!(
+            (exit_code(&e), e.to_string().as_str()),
+            (1, "checksum mismatch")
+        );
+        assert_eq!(io.stdout.to_stdout()[n..], *"a.txt: OK\nb.txt: FAILED\n");
+        let e = run_out(&mut io, &["hash", "--check", "SUMS", "--json"]).unwrap_err();
+        assert_eq!(e.to_string(), "checksum mismatch: b.txt");
+        // a missing file
+        io.write("SUMS", format!("{a}  a.txt\n{a}  c.txt\n").as_bytes())
+            .unwrap();
+        let n = io.stdout.to_stdout().len();
+        let e = run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap_err();
+        assert_eq!(e.to_string(), "checksum mismatch");
+        assert_eq!(
+            io.stdout.to_stdout()[n..],
+            *"a.txt: OK\nc.txt: FAILED open or read\n"
+        );
+        // a path with ` (` is not a BSD-style line.
+        io.write("a (1).txt", b"Hello, world!").unwrap();
+        io.write("SUMS", format!("{a}  a (1).txt\n").as_bytes())
+            .unwrap();
+        assert_eq!(
+            run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap(),
+            "a (1).txt: OK\n"
+        );
+        io.write("SUMS", b"x a.txt\n").unwrap();
+        let e = run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap_err();
+        assert_eq!(e.to_string(), "invalid checksum line");
+    }
+
+    const HELLO_SHA256: &str = "315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3";
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_hash_sha256() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let a = run_out(&mut io, &["hash", "a.txt"]).unwrap();
+        let out = run_out(&mut io, &["hash", "a.txt", "--also", "sha256"]).unwrap();
+        assert_eq!(out, format!("{a}SHA256 (a.txt) = {HELLO_SHA256}\n"));
+        let j = run_out(&mut io, &["hash", "a.txt", "--also", "sha256", "--json"]).unwrap();
+        assert_eq!(
+            j,
+            format!(
+                "{{\"hash\":\"{}\",\"sha256\":\"{HELLO_SHA256}\"}}\n",
+                a.trim_end()
+            )
+        );
+        io.write("SUMS", out.lines().nth(1).unwrap().as_bytes())
+            .unwrap();
+        assert_eq!(
+            run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap(),
+            "a.txt: OK\n"
+        );
+        io.write("SUMS", b"SHA224 (a.txt) = 00").unwrap();
+        let e = run_out(&mut io, &["hash", "--check", "SUMS"]).unwrap_err();
+        assert_eq!(e.to_string(), "checksum mismatch");
+        let e = run_out(&mut io, &["hash", "a.txt", "--also", "md5"]).unwrap_err();
+        assert_eq!(e.to_string(), "unknown digest");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_manifest() {
+        let mut io = VirtualIo::new(&[]);
+        io.create_dir("a").unwrap();
+        io.write("a/x.txt", b"Hello, world!").unwrap();
+        io.write("a/y.txt", &[7; 10_000]).unwrap();
+        let d = run_out(&mut io, &["add", "a"]).unwrap()[..45].to_owned();
+        let x = run_out(&mut io, &["hash", "a/x.txt"]).unwrap()[..45].to_owned();
+        let y = run_out(&mut io, &["hash", "a/y.txt"]).unwrap()[..45].to_owned();
+        let out = run_out(&mut io, &["manifest", &d]).unwrap();
+        assert_eq!(out, format!("{x}  x.txt\n{y}  y.txt\n"));
+        let out = run_out(&mut io, &["manifest", &d, "--sha256"]).unwrap();
+        assert_eq!(
+            out.lines().next().unwrap(),
+            format!("{HELLO_SHA256}  x.txt")
+        );
+        let j = run_out(&mut io, &["manifest", &d, "--sha256", "--json"]).unwrap();
+        assert!(j.starts_with(&format!(
+            "{{\"files\":[{{\"path\":\"x.txt\",\"sha256\":\"{HELLO_SHA256}\"}}"
+        )));
+        // `x.txt` is inlined as a data URI.
+        let d = run_out(&mut io, &["add", "a", "--inline-limit", "16"]).unwrap()[..45].to_owned();
+        let dir = run_out(&mut io, &["cat", &d]).unwrap();
+        assert!(dir.contains("\"x.txt\":\"data:"));
+        let out = run_out(&mut io, &["manifest", &d]).unwrap();
+        assert_eq!(out, format!("{x}  x.txt\n{y}  y.txt\n"));
+        let out = run_out(&mut io, &["manifest", &d, "--sha256"]).unwrap();
+        assert_eq!(
+            out.lines().next().unwrap(),
+            format!("{HELLO_SHA256}  x.txt")
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_dir_path() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let a = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        for path in [
+            "../b.txt",
+            "/b.txt",
+            "x//b.txt",
+            "./b.txt",
+            "x\\\\..\\\\b.txt",
+        ] {
+            let json = format!("{{\"directory\":{{\"{path}\":\"{a}\"}}}}");
+            io.write("d.json", json.as_bytes()).unwrap();
+            let d = run_out(&mut io, &["add", "d.json"]).unwrap()[..45].to_owned();
+            let e = run_out(&mut io, &["get", &d, "x/y/"]).unwrap_err();
+            assert_eq!(exit_code(&e), 6, "{path}");
+            assert!(io.metadata("x/b.txt").is_err());
+            assert!(io.metadata("b.txt").is_err());
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_corrupt_block() {
+        let mut io = VirtualIo::new(&[]);
+        io.write("a.txt", b"Hello, world!").unwrap();
+        let h = run_out(&mut io, &["add", "a.txt"]).unwrap()[..45].to_owned();
+        let path = format!("cdt0/roots/{}/{}/{}", &h[..2], &h[2..4], &h[4..]);
+        for (block, reason) in [
+            (&b" Hello, world?"[..], "digest mismatch"),
+            (b"", "empty block"),
+        ] {
+            io.write(&path, block).unwrap();
+            let e = run_out(&mut io, &["get", &h, "b.txt"]).unwrap_err();
+            assert_eq!(exit_code(&e), 5);
+            assert_eq!(e.to_string(), format!("corrupt block {h}: {reason}"));
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_cat() {
+        let mut io = VirtualIo::new(&["add", "a"]);
+        io.create_dir("a").unwrap();
+        io.write("a/b.txt", b"Hello world!").unwrap();
+        run(&io).unwrap();
+        let a = io.stdout.to_stdout()[..45].to_owned();
+        let n = io.stdout.to_stdout().len();
+        io.args = ["blockset", "cat", &(a.clone() + "/b.txt")]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        run(&io).unwrap();
+        assert_eq!(&io.stdout.to_stdout()[n..], "Hello world!");
+        io.args = ["blockset", "cat", &(a + "/c.txt")]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let e = run(&io).unwrap_err();
+        assert_eq!(
+            (exit_code(&e), e.to_string().as_str()),
+            (8, "path not found: c.txt")
+        );
+    }
 }
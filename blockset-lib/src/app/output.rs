@@ -0,0 +1,150 @@
+//! Command results as text or as one JSON document with `--json`.
+use std::io;
+
+use io_trait::Io;
+use nanvm_lib::{
+    js::{any::Any, any_cast::AnyCast, new::New},
+    mem::{global::GLOBAL, manager::Manager},
+    serializer::to_json::to_json,
+};
+
+use crate::{
+    common::{base32::ToBase32, hash_format::HashFormat, print::Print, status_line::StatusMode},
+    error::exit_code,
+    uint::u224::U224,
+};
+
+use super::{add::str_to_js_string, args::Matches, invalid_input, usage};
+
+pub const JSON: &str = "--json";
+
+pub const JSON_PROGRESS: &str = "--json-progress";
+
+pub const PROGRESS: &str = "--progress";
+
+pub const FORMAT: &str = "--format";
+
+pub enum Json {
+    Bool(bool),
+    /// JSON numbers are doubles, so integers above 2^53 lose precision. Sizes and counts of a
+    /// repository don't reach it.
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(n: u64) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl From<&U224> for Json {
+    fn from(d: &U224) -> Self {
+        Json::String(d.to_base32())
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Self {
+        Json::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+pub fn object<const N: usize>(p: [(&'static str, Json); N]) -> Json {
+    Json::Object(p.into())
+}
+
+fn to_any<M: Manager>(m: M, j: Json) -> Any<M::Dealloc> {
+    match j {
+        Json::Bool(b) => b.move_to_any(),
+        Json::Number(n) => n.move_to_any(),
+        Json::String(s) => str_to_js_string(m, s).move_to_any(),
+        Json::Array(v) => m.new_js_array(v.into_iter().map(|j| to_any(m, j)).collect::<Vec<_>>()),
+        Json::Object(p) => m.new_js_object(
+            p.into_iter()
+                .map(|(k, j)| (str_to_js_string(m, k), to_any(m, j)))
+                .collect::<Vec<_>>(),
+        ),
+    }
+}
+
+pub fn json_to_string(j: Json) -> io::Result<String> {
+    to_json(to_any(GLOBAL, j)).map_err(|_| invalid_input("to_json"))
+}
+
+/// Prints the JSON document with `--json`, otherwise prints the text.
+pub fn print<T: Io>(
+    io: &T,
+    m: &Matches,
+    json: Json,
+    text: impl FnOnce(&mut T::Stdout) -> io::Result<()>,
+) -> io::Result<()> {
+    let stdout = &mut io.stdout();
+    if m.flag(JSON) {
+        stdout.println([json_to_string(json)?.as_str()])
+    } else {
+        text(stdout)
+    }
+}
+
+/// Prints `{"error":...,"code":...}` with `--json`.
+pub fn print_error(io: &impl Io, m: &Matches, e: &io::Error) -> io::Result<()> {
+    if !m.flag(JSON) {
+        return Ok(());
+    }
+    let j = object([
+        ("error", e.to_string().into()),
+        ("code", (exit_code(e) as u64).into()),
+    ]);
+    io.stdout().println([json_to_string(j)?.as_str()])
+}
+
+/// `--progress` overrides the mode which depends on whether stderr is a TTY. `--json` turns
+/// the progress off by default.
+pub fn status_mode(m: &Matches) -> io::Result<StatusMode> {
+    if m.flag(JSON_PROGRESS) {
+        return Ok(StatusMode::Json);
+    }
+    Ok(match m.value(PROGRESS) {
+        None if m.flag(JSON) => StatusMode::Silent,
+        None | Some("auto") => StatusMode::Auto,
+        Some("terminal") => StatusMode::Terminal,
+        Some("plain") => StatusMode::Plain,
+        Some("none") => StatusMode::Silent,
+        Some("json") => StatusMode::Json,
+        Some(_) => return Err(usage("unknown progress mode")),
+    })
+}
+
+pub fn hash_format(m: &Matches) -> io::Result<HashFormat> {
+    match m.value(FORMAT) {
+        None => Ok(HashFormat::Base32),
+        Some(f) => HashFormat::from_name(f).ok_or_else(|| usage("unknown hash format")),
+    }
+}
@@ -0,0 +1,84 @@
+use std::io;
+
+use io_trait::Io;
+
+use crate::{
+    cdt::proof::{self, verify},
+    common::{base32::ToBase32, print::Print},
+    forest::file::FileForest,
+};
+
+use super::{
+    args::Matches,
+    get_hash, invalid_input,
+    output::{object, print},
+    read_file, str_to_hash, usage,
+};
+
+fn get_u64(a: &mut impl Iterator<Item = String>, name: &str) -> io::Result<u64> {
+    a.next()
+        .ok_or_else(|| usage(&("missing ".to_owned() + name)))?
+        .parse()
+        .map_err(|_| invalid_input("invalid range"))
+}
+
+fn get_file(a: &mut impl Iterator<Item = String>, name: &str) -> io::Result<String> {
+    a.next()
+        .ok_or_else(|| usage(&("missing ".to_owned() + name)))
+}
+
+/// Prints `<root> <offset> <len>` and proof tokens.
+pub fn prove(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let d = get_hash(a)?;
+    let offset = get_u64(a, "offset")?;
+    let len = get_u64(a, "length")?;
+    let end = offset
+        .checked_add(len)
+        .ok_or(invalid_input("invalid range"))?;
+    let tokens = proof::prove(&FileForest(io), &d, offset..end)?;
+    let json = object([
+        ("hash", (&d).into()),
+        ("offset", offset.into()),
+        ("len", len.into()),
+        ("proof", tokens.clone().into()),
+    ]);
+    print(io, m, json, |stdout| {
+        stdout.println([
+            d.to_base32().as_str(),
+            " ",
+            offset.to_string().as_str(),
+            " ",
+            len.to_string().as_str(),
+        ])?;
+        stdout.println([tokens.join(" ").as_str()])
+    })
+}
+
+pub fn verify_proof(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let proof = String::from_utf8(read_file(io, &get_file(a, "proof")?)?)
+        .map_err(|_| invalid_input("invalid proof"))?;
+    let excerpt = read_file(io, &get_file(a, "excerpt")?)?;
+    let (header, tokens) = proof
+        .split_once('\n')
+        .ok_or(invalid_input("invalid proof"))?;
+    let mut h = header.split(' ');
+    let d = str_to_hash(h.next().ok_or(invalid_input("invalid proof"))?)?;
+    let offset: u64 = h
+        .next()
+        .and_then(|o| o.parse().ok())
+        .ok_or(invalid_input("invalid proof"))?;
+    if h.next() != Some(excerpt.len().to_string().as_str()) {
+        return Err(invalid_input("invalid proof"));
+    }
+    if verify(tokens.split_whitespace(), &excerpt) != Some((d, offset)) {
+        return Err(invalid_input("invalid proof"));
+    }
+    let json = object([
+        ("hash", (&d).into()),
+        ("offset", offset.into()),
+        ("len", excerpt.len().into()),
+    ]);
+    print(io, m, json, |stdout| stdout.println(["valid: ", header]))
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, Cursor},
+};
+
+use io_trait::Io;
+use nanvm_lib::{
+    js::{any_cast::AnyCast, js_array::JsArrayRef, js_object::JsObjectRef, new::New},
+    mem::{global::GLOBAL, manager::Manager},
+    serializer::to_json::to_json,
+};
+
+use crate::{
+    common::{base32::ToBase32, print::Print, progress::State, status_line::StatusLine},
+    forest::{file::FileForest, tree_add::ForestTreeAdd},
+    uint::u224::U224,
+};
+
+use super::{
+    add::str_to_js_string,
+    args::Matches,
+    get::read_json_block,
+    get_hash, get_property, get_string_property, invalid_input, js_string_to_string,
+    output::{object, print, Json},
+    read_to_tree, str_to_hash, try_move,
+};
+
+pub const REVISION: &str = "revision";
+
+const PREVIOUS: &str = "previous";
+
+const CURRENT: &str = "current";
+
+pub struct Revision {
+    pub previous: Vec<U224>,
+    pub current: U224,
+}
+
+fn revision_to_json<M: Manager>(m: M, revision: &Revision) -> io::Result<String> {
+    let previous = m.new_js_array(
+        revision
+            .previous
+            .iter()
+            .map(|p| str_to_js_string(m, p.to_base32()).move_to_any())
+            .collect::<Vec<_>>(),
+    );
+    let current = str_to_js_string(m, revision.current.to_base32()).move_to_any();
+    let r = m.new_js_object([
+        (str_to_js_string(m, PREVIOUS), previous),
+        (str_to_js_string(m, CURRENT), current),
+    ]);
+    let block = m.new_js_object([(str_to_js_string(m, REVISION), r)]);
+    to_json(block).map_err(|_| invalid_input("to_json"))
+}
+
+/// Stores a block in the local repository and returns its hash.
+pub fn store_block(io: &impl Io, v: Vec<u8>) -> io::Result<String> {
+    let p = State {
+        total: v.len() as u64,
+        current: 0,
+    };
+    read_to_tree(
+        ForestTreeAdd::new(FileForest(io)),
+        Cursor::new(v),
+        &mut StatusLine::silent(io),
+        false,
+        p,
+        &mut 0,
+    )
+}
+
+pub fn store_json(io: &impl Io, json: String) -> io::Result<String> {
+    store_block(io, json.into_bytes())
+}
+
+pub fn store_revision(io: &impl Io, revision: &Revision) -> io::Result<String> {
+    store_json(io, revision_to_json(GLOBAL, revision)?)
+}
+
+pub fn read_revision(io: &impl Io, d: &U224) -> io::Result<Revision> {
+    let json = read_json_block(io, GLOBAL, d)?;
+    let r = try_move::<_, JsObjectRef<_>>(get_property(&json, REVISION)?)?;
+    let previous = try_move::<_, JsArrayRef<_>>(get_property(&r, PREVIOUS)?)?
+        .items()
+        .iter()
+        .map(|p| str_to_hash(&js_string_to_string(&try_move(p.clone())?)?))
+        .collect::<io::Result<_>>()?;
+    let current = str_to_hash(&get_string_property(&r, CURRENT)?)?;
+    Ok(Revision { previous, current })
+}
+
+pub fn commit(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let current = get_hash(a)?;
+    let previous = m
+        .values("--parent")
+        .map(str_to_hash)
+        .collect::<io::Result<Vec<_>>>()?;
+    // all parents should be revisions.
+    for p in &previous {
+        read_revision(io, p)?;
+    }
+    let k = store_revision(io, &Revision { previous, current })?;
+    print(io, m, object([("revision", k.as_str().into())]), |stdout| {
+        stdout.println([k.as_str()])
+    })
+}
+
+pub fn log(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let mut list = Vec::default();
+    let mut visited = BTreeSet::default();
+    let mut stack = [get_hash(a)?].to_vec();
+    while let Some(d) = stack.pop() {
+        if !visited.insert(d) {
+            continue;
+        }
+        let Revision { previous, current } = read_revision(io, &d)?;
+        list.push((d, current));
+        stack.extend(previous.into_iter().rev());
+    }
+    let json = list
+        .iter()
+        .map(|(d, c)| object([(REVISION, d.into()), ("hash", c.into())]))
+        .collect();
+    print(io, m, object([("log", Json::Array(json))]), |stdout| {
+        list.iter().try_for_each(|(d, c)| {
+            stdout.println([d.to_base32().as_str(), " ", c.to_base32().as_str()])
+        })
+    })
+}
@@ -0,0 +1,170 @@
+use std::io;
+
+use io_trait::Io;
+use nanvm_lib::{
+    js::{any_cast::AnyCast, js_object::JsObjectRef, new::New},
+    mem::{
+        global::GLOBAL,
+        manager::{Dealloc, Manager},
+    },
+    serializer::to_json::to_json,
+};
+
+use crate::{
+    common::{
+        base32::ToBase32,
+        base64::{from_base64_str, to_base64},
+        print::Print,
+    },
+    ed25519::{public_key, sign as ed25519_sign, verify, PublicKey, SecretKey, Signature},
+    tsp::verify as verify_time_stamp,
+    uint::u224::U224,
+};
+
+use super::{
+    add::str_to_js_string,
+    args::Matches,
+    get::read_json_block,
+    get_hash, get_property, get_string_property, invalid_input,
+    output::{object, print, Json},
+    read_file,
+    revision::store_json,
+    str_to_hash,
+    time_stamp::read_certificate,
+    trust::index_signature,
+    try_move, usage,
+};
+
+pub const SIGNATURE: &str = "signature";
+
+const PUBLIC_KEY: &str = "publicKey";
+
+const DATA_ADDRESS: &str = "dataAddress";
+
+const TIME_STAMP: &str = "timeStamp";
+
+pub struct SignatureBlock {
+    pub public_key: PublicKey,
+    pub data_address: U224,
+    pub signature: Signature,
+    /// A DER encoded RFC 3161 `TimeStampToken` of the data address.
+    pub time_stamp: Option<Vec<u8>>,
+}
+
+impl SignatureBlock {
+    /// Signs the base32 form of the data address.
+    pub fn new(sk: &SecretKey, data_address: &U224) -> Self {
+        Self {
+            public_key: public_key(sk),
+            data_address: *data_address,
+            signature: ed25519_sign(sk, data_address.to_base32().as_bytes()),
+            time_stamp: None,
+        }
+    }
+    pub fn verify(&self) -> bool {
+        verify(
+            &self.public_key,
+            self.data_address.to_base32().as_bytes(),
+            &self.signature,
+        )
+    }
+}
+
+fn signature_to_json<M: Manager>(m: M, s: &SignatureBlock) -> io::Result<String> {
+    let property = |name: &str, value: String| {
+        (
+            str_to_js_string(m, name),
+            str_to_js_string(m, value).move_to_any(),
+        )
+    };
+    let mut properties = [
+        property(PUBLIC_KEY, to_base64(&s.public_key)),
+        property(DATA_ADDRESS, s.data_address.to_base32()),
+    ]
+    .to_vec();
+    if let Some(t) = &s.time_stamp {
+        properties.push(property(TIME_STAMP, to_base64(t)));
+    }
+    properties.push(property(SIGNATURE, to_base64(&s.signature)));
+    let signature = m.new_js_object(properties);
+    let block = m.new_js_object([(str_to_js_string(m, SIGNATURE), signature)]);
+    to_json(block).map_err(|_| invalid_input("to_json"))
+}
+
+pub fn store_signature(io: &impl Io, s: &SignatureBlock) -> io::Result<String> {
+    let k = store_json(io, signature_to_json(GLOBAL, s)?)?;
+    index_signature(io, &k)?;
+    Ok(k)
+}
+
+fn from_base64_array<const N: usize>(s: &str) -> io::Result<[u8; N]> {
+    from_base64_str(s)
+        .and_then(|v| v.try_into().ok())
+        .ok_or(invalid_input("invalid base64"))
+}
+
+pub fn read_signature(io: &impl Io, d: &U224) -> io::Result<SignatureBlock> {
+    signature_from_json(&read_json_block(io, GLOBAL, d)?)
+}
+
+pub fn signature_from_json<D: Dealloc>(json: &JsObjectRef<D>) -> io::Result<SignatureBlock> {
+    let s = try_move::<_, JsObjectRef<_>>(get_property(json, SIGNATURE)?)?;
+    Ok(SignatureBlock {
+        public_key: from_base64_array(&get_string_property(&s, PUBLIC_KEY)?)?,
+        data_address: str_to_hash(&get_string_property(&s, DATA_ADDRESS)?)?,
+        signature: from_base64_array(&get_string_property(&s, SIGNATURE)?)?,
+        time_stamp: match get_property(&s, TIME_STAMP) {
+            Ok(_) => Some(
+                from_base64_str(&get_string_property(&s, TIME_STAMP)?)
+                    .ok_or(invalid_input("invalid base64"))?,
+            ),
+            Err(_) => None,
+        },
+    })
+}
+
+pub fn read_secret_key(io: &impl Io, path: &str) -> io::Result<SecretKey> {
+    read_file(io, path)?
+        .try_into()
+        .map_err(|_| invalid_input("invalid key"))
+}
+
+pub fn sign(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let d = get_hash(a)?;
+    let sk = read_secret_key(io, m.value("--key").ok_or(usage("missing key file"))?)?;
+    let k = store_signature(io, &SignatureBlock::new(&sk, &d))?;
+    print(
+        io,
+        m,
+        object([("signature", k.as_str().into())]),
+        |stdout| stdout.println([k.as_str()]),
+    )
+}
+
+pub fn verify_sig(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let d = get_hash(a)?;
+    let s = read_signature(io, &d)?;
+    if !s.verify() {
+        return Err(invalid_input("invalid signature"));
+    }
+    // signature blocks from other repositories become known to `trust`.
+    index_signature(io, &d.to_base32())?;
+    let time = match m.value("--ca") {
+        Some(ca) => {
+            let t = s.time_stamp.ok_or(invalid_input("missing time stamp"))?;
+            let time = verify_time_stamp(&t, &read_certificate(io, ca)?, &s.data_address)
+                .map_err(invalid_input)?;
+            Some(time)
+        }
+        None => None,
+    };
+    let mut json = Vec::from([("verified", (&s.data_address).into())]);
+    json.extend(time.iter().map(|t| ("time", t.as_str().into())));
+    print(io, m, Json::Object(json), |stdout| {
+        stdout.println(["verified: ", s.data_address.to_base32().as_str()])?;
+        time.iter()
+            .try_for_each(|t| stdout.println(["time: ", t.as_str()]))
+    })
+}
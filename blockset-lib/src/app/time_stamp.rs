@@ -0,0 +1,60 @@
+use std::io;
+
+use io_trait::Io;
+
+use crate::{
+    asn1::pem::from_pem,
+    common::print::Print,
+    tsp::{message_imprint, request, tst_info},
+};
+
+use super::{
+    args::Matches,
+    get_hash, invalid_input,
+    output::{object, print},
+    read_file,
+    sign::{read_signature, store_signature},
+    usage, with_path,
+};
+
+fn get_file(a: &mut impl Iterator<Item = String>) -> io::Result<String> {
+    a.next().ok_or(usage("missing file name"))
+}
+
+/// Reads a DER or PEM encoded certificate.
+pub fn read_certificate(io: &impl Io, path: &str) -> io::Result<Vec<u8>> {
+    let v = read_file(io, path)?;
+    if !v.starts_with(b"-----BEGIN ") {
+        return Ok(v);
+    }
+    core::str::from_utf8(&v)
+        .ok()
+        .and_then(from_pem)
+        .ok_or(invalid_input("invalid PEM"))
+}
+
+pub fn ts_request(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let d = get_hash(a)?;
+    let path = get_file(a)?;
+    let request = request(&d).map_err(invalid_input)?;
+    io.write(&path, &request).map_err(with_path(&path))?;
+    print(io, m, object([("path", path.into())]), |_| Ok(()))
+}
+
+pub fn ts_attach(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let mut s = read_signature(io, &get_hash(a)?)?;
+    let token = read_file(io, &get_file(a)?)?;
+    if tst_info(&token).map_err(invalid_input)?.hashed_message != message_imprint(&s.data_address) {
+        return Err(invalid_input("message imprint mismatch"));
+    }
+    s.time_stamp = Some(token);
+    let k = store_signature(io, &s)?;
+    print(
+        io,
+        m,
+        object([("signature", k.as_str().into())]),
+        |stdout| stdout.println([k.as_str()]),
+    )
+}
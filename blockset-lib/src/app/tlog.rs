@@ -0,0 +1,329 @@
+use std::{io, iter::once};
+
+use io_trait::Io;
+use nanvm_lib::{
+    js::{any_cast::AnyCast, js_array::JsArrayRef, js_object::JsObjectRef, new::New},
+    mem::{global::GLOBAL, manager::Manager},
+    serializer::to_json::to_json,
+};
+
+use crate::{
+    common::{base32::ToBase32, print::Print},
+    error::BlocksetError,
+    tlog::{
+        consistency_proof, empty_hash, inclusion_proof, leaf_hash, node_hash, split,
+        verify_consistency, verify_inclusion,
+    },
+    uint::u224::U224,
+};
+
+use super::{
+    add::{property, str_to_js_string},
+    args::Matches,
+    get::read_json_block,
+    get_hash, get_property, get_string_property, invalid_input, js_string_to_string,
+    output::{object, print, Json},
+    revision::store_json,
+    str_to_hash, try_move, usage,
+};
+
+pub const TLOG: &str = "tlog";
+
+/// A block of a perfect subtree with more than one leaf.
+const TLOG_NODE: &str = "tlognode";
+
+/// The file next to the repository which contains the hash of the log head block.
+const HEAD: &str = "cdt0-tlog";
+
+const SIZE: &str = "size";
+
+const FRONTIER: &str = "frontier";
+
+const HASH: &str = "hash";
+
+const LEFT: &str = "left";
+
+const RIGHT: &str = "right";
+
+/// A perfect subtree is referenced by its entry if it's a leaf or by its `tlognode` block.
+struct Node {
+    hash: U224,
+    left: U224,
+    right: U224,
+}
+
+fn read_node(io: &impl Io, d: &U224) -> io::Result<Node> {
+    let json = read_json_block(io, GLOBAL, d)?;
+    let n = try_move::<_, JsObjectRef<_>>(get_property(&json, TLOG_NODE)?)?;
+    let get = |name| str_to_hash(&get_string_property(&n, name)?);
+    Ok(Node {
+        hash: get(HASH)?,
+        left: get(LEFT)?,
+        right: get(RIGHT)?,
+    })
+}
+
+fn store_node<M: Manager>(io: &impl Io, m: M, node: &Node) -> io::Result<U224> {
+    let n = m.new_js_object([
+        property(m, HASH, node.hash.to_base32()),
+        property(m, LEFT, node.left.to_base32()),
+        property(m, RIGHT, node.right.to_base32()),
+    ]);
+    let block = m.new_js_object([(str_to_js_string(m, TLOG_NODE), n)]);
+    str_to_hash(&store_json(
+        io,
+        to_json(block).map_err(|_| invalid_input("to_json"))?,
+    )?)
+}
+
+/// `MTH` of a perfect subtree.
+fn subtree_hash(io: &impl Io, key: &U224, height: u32) -> io::Result<U224> {
+    if height == 0 {
+        Ok(leaf_hash(key))
+    } else {
+        Ok(read_node(io, key)?.hash)
+    }
+}
+
+/// The log head keeps roots of the perfect subtrees on the right edge of the tree, from the
+/// largest to the smallest. Their sizes are the bits of `size`, so appending an entry and
+/// building a proof read `O(log(size))` blocks.
+#[derive(Default)]
+struct Log {
+    size: usize,
+    frontier: Vec<U224>,
+}
+
+impl Log {
+    /// `(start, height, key)` of the frontier subtrees.
+    fn subtrees(&self) -> impl Iterator<Item = (usize, u32, &U224)> {
+        let heights = (0..usize::BITS).rev().filter(|h| self.size >> h & 1 == 1);
+        let mut start = 0;
+        heights.zip(&self.frontier).map(move |(h, key)| {
+            let result = (start, h, key);
+            start += 1 << h;
+            result
+        })
+    }
+    fn push(&mut self, io: &impl Io, entry: &U224) -> io::Result<()> {
+        let mut key = *entry;
+        let mut height = 0;
+        // equal subtrees are merged like the carry of a binary increment.
+        while self.size >> height & 1 == 1 {
+            let left = self
+                .frontier
+                .pop()
+                .ok_or(invalid_input("invalid log head"))?;
+            let hash = node_hash(
+                &subtree_hash(io, &left, height)?,
+                &subtree_hash(io, &key, height)?,
+            );
+            let right = key;
+            key = store_node(io, GLOBAL, &Node { hash, left, right })?;
+            height += 1;
+        }
+        self.frontier.push(key);
+        self.size += 1;
+        Ok(())
+    }
+    /// `MTH(D[lo:lo + 2^height])` of an aligned perfect subtree.
+    fn perfect_hash(&self, io: &impl Io, lo: usize, height: u32) -> io::Result<U224> {
+        let (mut start, mut h, key) = self
+            .subtrees()
+            .find(|&(start, h, _)| start <= lo && lo < start + (1 << h))
+            .ok_or(invalid_input("invalid size"))?;
+        let mut key = *key;
+        while h > height {
+            let node = read_node(io, &key)?;
+            h -= 1;
+            if lo < start + (1 << h) {
+                key = node.left;
+            } else {
+                start += 1 << h;
+                key = node.right;
+            }
+        }
+        subtree_hash(io, &key, h)
+    }
+    /// `MTH(D[lo:hi])`, perfect subtrees are read from their blocks.
+    fn mth(&self, io: &impl Io, lo: usize, hi: usize) -> io::Result<U224> {
+        let n = hi - lo;
+        if n == 0 {
+            return Ok(empty_hash());
+        }
+        if n.is_power_of_two() && lo.is_multiple_of(n) {
+            return self.perfect_hash(io, lo, n.trailing_zeros());
+        }
+        let k = lo + split(n);
+        Ok(node_hash(&self.mth(io, lo, k)?, &self.mth(io, k, hi)?))
+    }
+    /// The index of the first entry `d`, it reads every block of the log.
+    fn find(&self, io: &impl Io, d: &U224) -> io::Result<Option<usize>> {
+        fn find_in(
+            io: &impl Io,
+            key: &U224,
+            h: u32,
+            start: usize,
+            d: &U224,
+        ) -> io::Result<Option<usize>> {
+            if h == 0 {
+                return Ok((key == d).then_some(start));
+            }
+            let node = read_node(io, key)?;
+            Ok(match find_in(io, &node.left, h - 1, start, d)? {
+                Some(i) => Some(i),
+                None => find_in(io, &node.right, h - 1, start + (1 << (h - 1)), d)?,
+            })
+        }
+        for (start, h, key) in self.subtrees() {
+            if let Some(i) = find_in(io, key, h, start, d)? {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn log_to_json<M: Manager>(m: M, log: &Log) -> io::Result<String> {
+    let frontier = m.new_js_array(
+        log.frontier
+            .iter()
+            .map(|k| str_to_js_string(m, k.to_base32()).move_to_any())
+            .collect::<Vec<_>>(),
+    );
+    let t = m.new_js_object([
+        property(m, SIZE, log.size.to_string()),
+        (str_to_js_string(m, FRONTIER), frontier),
+    ]);
+    let block = m.new_js_object([(str_to_js_string(m, TLOG), t)]);
+    to_json(block).map_err(|_| invalid_input("to_json"))
+}
+
+fn read_log(io: &impl Io) -> io::Result<Log> {
+    let Ok(v) = io.read(HEAD) else {
+        return Ok(Log::default());
+    };
+    let invalid = || invalid_input("invalid log head");
+    let s = String::from_utf8(v).map_err(|_| invalid())?;
+    let json = read_json_block(io, GLOBAL, &str_to_hash(s.trim())?)?;
+    let t = try_move::<_, JsObjectRef<_>>(get_property(&json, TLOG)?)?;
+    let size = get_string_property(&t, SIZE)?
+        .parse::<usize>()
+        .map_err(|_| invalid())?;
+    let frontier = try_move::<_, JsArrayRef<_>>(get_property(&t, FRONTIER)?)?
+        .items()
+        .iter()
+        .map(|k| str_to_hash(&js_string_to_string(&try_move(k.clone())?)?))
+        .collect::<io::Result<Vec<_>>>()?;
+    if frontier.len() != size.count_ones() as usize {
+        return Err(invalid());
+    }
+    Ok(Log { size, frontier })
+}
+
+fn write_log(io: &impl Io, log: &Log) -> io::Result<()> {
+    let k = store_json(io, log_to_json(GLOBAL, log)?)?;
+    io.write(HEAD, k.as_bytes())
+}
+
+fn print_line(io: &impl Io, m: &Matches, json: Json, items: &[String]) -> io::Result<()> {
+    print(io, m, json, |stdout| {
+        stdout.println([items.join(" ").as_str()])
+    })
+}
+
+fn append(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let entry = get_hash(&mut m.args())?;
+    let mut log = read_log(io)?;
+    log.push(io, &entry)?;
+    write_log(io, &log)?;
+    let n = log.size;
+    let root = log.mth(io, 0, n)?;
+    let json = object([("size", n.into()), ("root", (&root).into())]);
+    print_line(io, m, json, &[n.to_string(), root.to_base32()])
+}
+
+fn get_size(a: &mut impl Iterator<Item = String>) -> io::Result<usize> {
+    a.next()
+        .ok_or(usage("missing size"))?
+        .parse()
+        .map_err(|_| invalid_input("invalid size"))
+}
+
+fn prove(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let log = read_log(io)?;
+    let n = log.size;
+    let root = log.mth(io, 0, n)?;
+    let mth = &mut |a, b| log.mth(io, a, b);
+    let (mut line, path) = if let Some(size) = m.value("--consistency") {
+        let m = get_size(&mut once(size.to_owned()))?;
+        if m == 0 || m > n {
+            return Err(invalid_input("invalid size"));
+        }
+        let line = [
+            "consistency".to_owned(),
+            m.to_string(),
+            mth(0, m)?.to_base32(),
+        ];
+        (line.to_vec(), consistency_proof(n, m, mth)?)
+    } else {
+        let arg = m.args().next().ok_or(usage("missing hash"))?;
+        let d = str_to_hash(&arg)?;
+        let not_found = || io::Error::from(BlocksetError::NotFound("entry not found".to_owned()));
+        let i = match m.value("--index") {
+            Some(i) => {
+                let i = get_size(&mut once(i.to_owned()))?;
+                if i >= n || mth(i, i + 1)? != leaf_hash(&d) {
+                    return Err(not_found());
+                }
+                i
+            }
+            None => log.find(io, &d)?.ok_or_else(not_found)?,
+        };
+        let line = ["inclusion".to_owned(), arg, i.to_string()];
+        (line.to_vec(), inclusion_proof(n, i, mth)?)
+    };
+    line.extend([n.to_string(), root.to_base32()]);
+    line.extend(path.iter().map(|p| p.to_base32()));
+    // the proof is the argument list of `tlog verify`.
+    print_line(io, m, object([("proof", line.clone().into())]), &line)
+}
+
+fn verify(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let kind = a.next().ok_or(usage("missing proof"))?;
+    let valid = match kind.as_str() {
+        "inclusion" => {
+            let entry = get_hash(a)?;
+            let index = get_size(a)?;
+            let size = get_size(a)?;
+            let root = get_hash(a)?;
+            let path = a.map(|p| str_to_hash(&p)).collect::<io::Result<Vec<_>>>()?;
+            verify_inclusion(&leaf_hash(&entry), index, size, &path, &root)
+        }
+        "consistency" => {
+            let first = get_size(a)?;
+            let first_root = get_hash(a)?;
+            let second = get_size(a)?;
+            let second_root = get_hash(a)?;
+            let path = a.map(|p| str_to_hash(&p)).collect::<io::Result<Vec<_>>>()?;
+            verify_consistency(first, second, &first_root, &second_root, &path)
+        }
+        _ => return Err(usage("unknown proof")),
+    };
+    if !valid {
+        return Err(invalid_input("invalid proof"));
+    }
+    print(io, m, object([("valid", true.into())]), |stdout| {
+        stdout.println(["valid"])
+    })
+}
+
+pub fn tlog(io: &impl Io, m: &Matches) -> io::Result<()> {
+    match m.subcommand(1) {
+        "append" => append(io, m),
+        "prove" => prove(io, m),
+        "verify" => verify(io, m),
+        _ => Err(usage("unknown tlog command")),
+    }
+}
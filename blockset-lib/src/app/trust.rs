@@ -0,0 +1,168 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Write},
+};
+
+use io_trait::Io;
+use nanvm_lib::mem::global::GLOBAL;
+
+use crate::{
+    cdt::node_type::NodeType,
+    common::{base32::ToBase32, print::Print},
+    forest::{file::FileForest, node_id::ForestNodeId, Forest},
+    uint::u224::U224,
+};
+
+use super::{
+    args::Matches,
+    get::parse_json,
+    get_hash, invalid_input,
+    key::public_key_hash,
+    output::{object, print, Json},
+    sign::signature_from_json,
+    str_to_hash, try_move, usage, with_path,
+};
+
+/// The file next to the repository which contains hashes of trusted public keys.
+pub const ANCHORS: &str = "cdt0-trust";
+
+/// The file next to the repository which lists hashes of stored signature blocks, so `trust`
+/// doesn't restore every root block.
+pub const SIGNATURES: &str = "cdt0-signatures";
+
+const MAX_PATH: usize = 5;
+
+/// Signature blocks are small, so we stop restoring other blocks early.
+const MAX_SIZE: usize = 0x10000;
+
+/// Collects a JSON object. Signature blocks are recognized by their `signature` property after
+/// parsing.
+struct SignatureWriter(Vec<u8>);
+
+impl Write for SignatureWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        let first = self.0.iter().find(|c| !c.is_ascii_whitespace());
+        if first.is_some_and(|&c| c != b'{') || self.0.len() > MAX_SIZE {
+            return Err(invalid_input("not a signature block"));
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A verified signature block, `(signer, signed)`.
+fn signature_edge(io: &impl Io, d: &U224) -> Option<(U224, U224)> {
+    let mut w = SignatureWriter(Vec::default());
+    FileForest(io)
+        .restore(&ForestNodeId::new(NodeType::Root, d), &mut w, |_, _| Ok(()))
+        .ok()?;
+    let s = signature_from_json(&try_move(parse_json(io, GLOBAL, w.0).ok()?).ok()?).ok()?;
+    if !s.verify() {
+        return None;
+    }
+    Some((public_key_hash(&s.public_key).ok()?, s.data_address))
+}
+
+/// Hashes, one per line. A missing file is empty.
+fn read_hashes(io: &impl Io, path: &str) -> io::Result<Vec<U224>> {
+    let Ok(v) = io.read(path) else {
+        return Ok(Vec::default());
+    };
+    String::from_utf8(v)
+        .map_err(|_| invalid_input(&("invalid ".to_owned() + path)))?
+        .split_whitespace()
+        .map(str_to_hash)
+        .collect()
+}
+
+/// Adds a signature block to `SIGNATURES` if it's not there yet.
+pub fn index_signature(io: &impl Io, k: &str) -> io::Result<()> {
+    let v = io.read(SIGNATURES).unwrap_or_default();
+    if v.split(|&c| c == b'\n').any(|line| line == k.as_bytes()) {
+        return Ok(());
+    }
+    let v = [v.as_slice(), k.as_bytes(), b"\n"].concat();
+    io.write(SIGNATURES, &v).map_err(with_path(SIGNATURES))
+}
+
+/// Returns `(key, signature)` pairs from an anchor to the key. An anchor has no signature.
+fn trust_path(
+    io: &impl Io,
+    signatures: &[U224],
+    anchors: &[U224],
+    max_path: usize,
+    key: &U224,
+) -> Option<Vec<(U224, Option<U224>)>> {
+    let mut edges = HashMap::<U224, Vec<(U224, U224)>>::default();
+    for s in signatures {
+        if let Some((signer, signed)) = signature_edge(io, s) {
+            if signer != signed {
+                edges.entry(signer).or_default().push((signed, *s));
+            }
+        }
+    }
+    let mut previous = HashMap::<U224, Option<(U224, U224)>>::default();
+    let mut queue = VecDeque::default();
+    for a in anchors {
+        if previous.insert(*a, None).is_none() {
+            queue.push_back((*a, 0));
+        }
+    }
+    while let Some((k, depth)) = queue.pop_front() {
+        if k == *key {
+            let mut result = Vec::default();
+            let mut k = k;
+            while let Some(p) = previous[&k] {
+                result.push((k, Some(p.1)));
+                k = p.0;
+            }
+            result.push((k, None));
+            result.reverse();
+            return Some(result);
+        }
+        if depth == max_path {
+            continue;
+        }
+        for (signed, s) in edges.get(&k).into_iter().flatten() {
+            if !previous.contains_key(signed) {
+                previous.insert(*signed, Some((k, *s)));
+                queue.push_back((*signed, depth + 1));
+            }
+        }
+    }
+    None
+}
+
+pub fn trust(io: &impl Io, m: &Matches) -> io::Result<()> {
+    let a = &mut m.args();
+    let key = get_hash(a)?;
+    let mut anchors = read_hashes(io, ANCHORS)?;
+    for a in m.values("--anchor") {
+        anchors.push(str_to_hash(a)?);
+    }
+    let max_path = match m.value("--max-path") {
+        Some(v) => v.parse().map_err(|_| usage("invalid max path"))?,
+        None => MAX_PATH,
+    };
+    let signatures = read_hashes(io, SIGNATURES)?;
+    let path = trust_path(io, &signatures, &anchors, max_path, &key)
+        .ok_or(invalid_input("untrusted key"))?;
+    // the anchor has no signature.
+    let json = path
+        .iter()
+        .map(|(k, s)| {
+            let mut p = Vec::from([("key", k.into())]);
+            p.extend(s.iter().map(|s| ("signature", s.into())));
+            Json::Object(p)
+        })
+        .collect();
+    print(io, m, object([("path", Json::Array(json))]), |stdout| {
+        path.iter().try_for_each(|(k, s)| {
+            let s = s.map_or("anchor".to_owned(), |s| s.to_base32());
+            stdout.println([k.to_base32().as_str(), " ", s.as_str()])
+        })
+    })
+}
@@ -0,0 +1,250 @@
+pub const BOOLEAN: u8 = 0x01;
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const UTC_TIME: u8 = 0x17;
+pub const GENERALIZED_TIME: u8 = 0x18;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+
+/// A constructed context-specific tag, `[n]`.
+pub const fn context(n: u8) -> u8 {
+    0xA0 | n
+}
+
+/// A DER element. `raw` includes the tag and the length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    pub content: &'a [u8],
+    pub raw: &'a [u8],
+}
+
+fn decode_len(v: &[u8]) -> Option<(usize, &[u8])> {
+    let (&first, rest) = v.split_first()?;
+    if first < 0x80 {
+        return Some((first as usize, rest));
+    }
+    // indefinite and too long lengths are not allowed.
+    let n = (first & 0x7F) as usize;
+    if n == 0 || n > 4 || rest.len() < n || rest[0] == 0 {
+        return None;
+    }
+    let len = rest[..n].iter().fold(0, |a, &b| (a << 8) | b as usize);
+    // the shortest form is required.
+    if len < 0x80 {
+        return None;
+    }
+    Some((len, &rest[n..]))
+}
+
+/// Decodes one element and returns the rest of the input.
+pub fn decode(v: &[u8]) -> Option<(Tlv<'_>, &[u8])> {
+    let (&tag, rest) = v.split_first()?;
+    // high tag numbers are not supported.
+    if tag & 0x1F == 0x1F {
+        return None;
+    }
+    let (len, rest) = decode_len(rest)?;
+    if rest.len() < len {
+        return None;
+    }
+    let header = v.len() - rest.len();
+    Some((
+        Tlv {
+            tag,
+            content: &rest[..len],
+            raw: &v[..header + len],
+        },
+        &rest[len..],
+    ))
+}
+
+pub fn decode_all(mut v: &[u8]) -> Option<Vec<Tlv<'_>>> {
+    let mut result = Vec::default();
+    while !v.is_empty() {
+        let (x, rest) = decode(v)?;
+        result.push(x);
+        v = rest;
+    }
+    Some(result)
+}
+
+impl<'a> Tlv<'a> {
+    pub fn expect(self, tag: u8) -> Option<Self> {
+        if self.tag == tag {
+            Some(self)
+        } else {
+            None
+        }
+    }
+    pub fn children(&self) -> Option<Vec<Tlv<'a>>> {
+        decode_all(self.content)
+    }
+    pub fn oid(&self) -> Option<Vec<u64>> {
+        decode_oid(self.expect(OBJECT_IDENTIFIER)?.content)
+    }
+}
+
+pub fn decode_oid(v: &[u8]) -> Option<Vec<u64>> {
+    let mut result = Vec::default();
+    let mut x = 0u64;
+    for (i, &b) in v.iter().enumerate() {
+        if (x == 0 && b == 0x80) || x >> 57 != 0 {
+            return None;
+        }
+        x = (x << 7) | (b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            if result.is_empty() {
+                let first = x.min(80) / 40;
+                result.extend([first, x - first * 40]);
+            } else {
+                result.push(x);
+            }
+            x = 0;
+        } else if i == v.len() - 1 {
+            return None;
+        }
+    }
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+fn encode_len(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        return [len as u8].to_vec();
+    }
+    let b = len.to_be_bytes();
+    let skip = b.iter().take_while(|&&x| x == 0).count();
+    [[0x80 | (b.len() - skip) as u8].as_slice(), &b[skip..]].concat()
+}
+
+pub fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    [[tag].as_slice(), &encode_len(content.len()), content].concat()
+}
+
+pub fn encode_all(tag: u8, items: &[Vec<u8>]) -> Vec<u8> {
+    encode(tag, &items.concat())
+}
+
+/// `None` if there are less than two arcs or the first two arcs are out of range.
+pub fn encode_oid(oid: &[u64]) -> Option<Vec<u8>> {
+    let [first, second, ..] = *oid else {
+        return None;
+    };
+    if first > 2 || (first < 2 && second >= 40) {
+        return None;
+    }
+    let mut result = Vec::default();
+    let first = (first * 40).checked_add(second)?;
+    for &x in [first].iter().chain(oid.iter().skip(2)) {
+        let n = (64 - x.leading_zeros() as usize).div_ceil(7).max(1);
+        for i in (0..n).rev() {
+            let b = ((x >> (i * 7)) & 0x7F) as u8;
+            result.push(if i == 0 { b } else { b | 0x80 });
+        }
+    }
+    Some(encode(OBJECT_IDENTIFIER, &result))
+}
+
+pub fn encode_integer(x: u64) -> Vec<u8> {
+    let v = [[0].as_slice(), &x.to_be_bytes()].concat();
+    let mut skip = v.iter().take_while(|&&b| b == 0).count().min(8);
+    // the high bit is a sign bit.
+    if v[skip] & 0x80 != 0 {
+        skip -= 1;
+    }
+    encode(INTEGER, &v[skip..])
+}
+
+pub fn encode_boolean(x: bool) -> Vec<u8> {
+    encode(BOOLEAN, &[if x { 0xFF } else { 0 }])
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{
+        decode, decode_all, encode, encode_all, encode_boolean, encode_integer, encode_oid,
+        OCTET_STRING, SEQUENCE,
+    };
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_oid() {
+        let f = |oid: &[u64], der: &[u8]| {
+            let e = encode_oid(oid).unwrap();
+            assert_eq!(e, der);
+            assert_eq!(decode(&e).unwrap().0.oid().unwrap(), oid);
+        };
+        // sha512
+        f(
+            &[2, 16, 840, 1, 101, 3, 4, 2, 3],
+            &[6, 9, 0x60, 0x86, 0x48, 1, 0x65, 3, 4, 2, 3],
+        );
+        // ed25519
+        f(&[1, 3, 101, 112], &[6, 3, 0x2B, 0x65, 0x70]);
+        f(&[2, 999, 3], &[6, 3, 0x88, 0x37, 3]);
+        assert_eq!(encode_oid(&[]), None);
+        assert_eq!(encode_oid(&[1]), None);
+        assert_eq!(encode_oid(&[3, 1]), None);
+        assert_eq!(encode_oid(&[1, 40]), None);
+        assert_eq!(encode_oid(&[2, u64::MAX]), None);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_integer() {
+        assert_eq!(encode_integer(0), [2, 1, 0]);
+        assert_eq!(encode_integer(1), [2, 1, 1]);
+        assert_eq!(encode_integer(0x7F), [2, 1, 0x7F]);
+        assert_eq!(encode_integer(0x80), [2, 2, 0, 0x80]);
+        assert_eq!(encode_integer(0x0100), [2, 2, 1, 0]);
+        assert_eq!(
+            encode_integer(u64::MAX),
+            [2, 9, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+        assert_eq!(encode_boolean(true), [1, 1, 0xFF]);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_len() {
+        let long = encode(OCTET_STRING, &[0; 200]);
+        assert_eq!(long[..3], [4, 0x81, 200]);
+        let s = encode_all(SEQUENCE, &[long.clone(), encode_boolean(false)]);
+        assert_eq!(s[..4], [0x30, 0x81, 206, 4]);
+        let (x, rest) = decode(&s).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(x.raw, s);
+        let c = x.children().unwrap();
+        assert_eq!(c.len(), 2);
+        assert_eq!(c[0].content, [0; 200]);
+        assert_eq!(c[1].raw, [1, 1, 0]);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid() {
+        // truncated
+        assert_eq!(decode(&[4, 2, 0]), None);
+        // indefinite length
+        assert_eq!(decode(&[0x30, 0x80, 0, 0]), None);
+        // not the shortest length
+        assert_eq!(decode(&[4, 0x81, 1, 0]), None);
+        assert_eq!(decode(&[4, 0x82, 0, 0x80]), None);
+        // high tag number
+        assert_eq!(decode(&[0x1F, 1, 0]), None);
+        assert_eq!(decode_all(&[4, 0, 4]), None);
+        // non-minimal and unterminated OID sub-identifiers
+        assert_eq!(decode(&[6, 2, 0x80, 1]).unwrap().0.oid(), None);
+        assert_eq!(decode(&[6, 1, 0x81]).unwrap().0.oid(), None);
+        assert_eq!(decode(&[6, 0]).unwrap().0.oid(), None);
+    }
+}
@@ -0,0 +1,3 @@
+//! https://www.itu.int/rec/T-REC-X.690
+pub mod der;
+pub mod pem;
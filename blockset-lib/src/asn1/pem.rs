@@ -0,0 +1,11 @@
+use crate::common::base64::from_base64_str;
+
+/// https://datatracker.ietf.org/doc/html/rfc7468
+pub fn from_pem(s: &str) -> Option<Vec<u8>> {
+    let mut lines = s.lines().map(str::trim);
+    lines.find(|l| l.starts_with("-----BEGIN "))?;
+    let body = lines
+        .take_while(|l| !l.starts_with("-----END "))
+        .collect::<String>();
+    from_base64_str(&body)
+}
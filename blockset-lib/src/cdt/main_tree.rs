@@ -22,6 +22,9 @@ impl<T: TreeAdd> MainTreeAdd<T> {
             state: default(),
         }
     }
+    pub fn tree_add(&mut self) -> &mut T {
+        &mut self.tree_add
+    }
     pub fn push(&mut self, c: u8) -> io::Result<u64> {
         let mut i = 0;
         let mut last0 = to_node_id(c);
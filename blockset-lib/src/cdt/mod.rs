@@ -1,5 +1,7 @@
 pub mod main_tree;
 pub mod node_id;
 pub mod node_type;
+pub mod proof;
 pub mod subtree;
 pub mod tree_add;
+pub mod writer;
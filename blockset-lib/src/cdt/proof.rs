@@ -0,0 +1,475 @@
+//! Proofs that a byte range belongs to a CDT root.
+//!
+//! A proof is a sequence of tokens in postfix order:
+//! - `b` is the next byte of the excerpt,
+//! - `m` merges two nodes,
+//! - a 64-digit hex number is a node of the tree outside of the range. A node of a hash left of
+//!   the range is followed by `:` and its length in bytes, so the verifier can compute the offset
+//!   of the excerpt. Lengths of nodes of data are in their ids. The root doesn't depend on
+//!   lengths of nodes of hashes, so the offset is only as good as the source of the proof.
+use std::{io, ops::Range, rc::Rc};
+
+use crate::{
+    common::hash_format::to_hex as bytes_to_hex,
+    error::BlocksetError,
+    forest::{
+        block::{Block, DATA},
+        corrupt,
+        node_id::ForestNodeId,
+        Forest, DIGEST_MISMATCH, MAX_DEPTH,
+    },
+    uint::{
+        u224::U224,
+        u256::{from_u224, to_u224, U256},
+    },
+};
+
+use super::{
+    node_id::{len, merge, root, to_node_id},
+    node_type::NodeType,
+    subtree::{SubTree, SubTreeNode},
+};
+
+enum Proof {
+    /// A node outside of the range with its length if it's left of the range.
+    Digest(U256, Option<u64>),
+    Byte,
+    Merge(Rc<Proof>, Rc<Proof>),
+}
+
+#[derive(Clone)]
+struct ProofNode {
+    node_id: U256,
+    start: u64,
+    end: u64,
+    proof: Rc<Proof>,
+    range: Range<u64>,
+}
+
+impl ProofNode {
+    fn new(node_id: U256, start: u64, end: u64, range: &Range<u64>, proof: Proof) -> Self {
+        let proof = if end <= range.start {
+            Proof::Digest(node_id, Some(end - start))
+        } else if range.end <= start {
+            Proof::Digest(node_id, None)
+        } else {
+            proof
+        };
+        Self {
+            node_id,
+            start,
+            end,
+            proof: Rc::new(proof),
+            range: range.clone(),
+        }
+    }
+}
+
+impl SubTreeNode for ProofNode {
+    fn node_id(&self) -> &U256 {
+        &self.node_id
+    }
+    fn merge(&self, b: &Self) -> Self {
+        // an empty node doesn't change the result of `merge`.
+        if len(&self.node_id) == 0 {
+            return b.clone();
+        }
+        if len(&b.node_id) == 0 {
+            return self.clone();
+        }
+        Self::new(
+            merge(&self.node_id, &b.node_id),
+            self.start,
+            b.end,
+            &self.range,
+            Proof::Merge(self.proof.clone(), b.proof.clone()),
+        )
+    }
+}
+
+/// Follows `MainTreeAdd` and collects the structure of the tree around the range.
+struct ProofBuilder {
+    range: Range<u64>,
+    position: u64,
+    state: Vec<SubTree<ProofNode>>,
+}
+
+fn to_hex(&[lo, hi]: &U256) -> String {
+    bytes_to_hex(&[hi.to_be_bytes(), lo.to_be_bytes()].concat())
+}
+
+fn from_hex(s: &str) -> Option<U256> {
+    if s.len() != 64 || !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hi = u128::from_str_radix(&s[..32], 16).ok()?;
+    let lo = u128::from_str_radix(&s[32..], 16).ok()?;
+    Some([lo, hi])
+}
+
+fn write_tokens(p: &Proof, result: &mut Vec<String>) {
+    match p {
+        Proof::Digest(d, Some(n)) if to_u224(d).is_some() => {
+            result.push(to_hex(d) + ":" + &n.to_string())
+        }
+        Proof::Digest(d, _) => result.push(to_hex(d)),
+        Proof::Byte => result.push("b".to_owned()),
+        Proof::Merge(a, b) => {
+            write_tokens(a, result);
+            write_tokens(b, result);
+            result.push("m".to_owned());
+        }
+    }
+}
+
+fn tokens(node: &ProofNode) -> Vec<String> {
+    let mut result = Vec::default();
+    write_tokens(&node.proof, &mut result);
+    result
+}
+
+impl ProofBuilder {
+    /// The content starts at the position.
+    fn at(range: Range<u64>, position: u64) -> Self {
+        Self {
+            range,
+            position,
+            state: Vec::default(),
+        }
+    }
+    fn push(&mut self, c: u8) {
+        let p = self.position;
+        self.position += 1;
+        self.push_node(ProofNode::new(
+            to_node_id(c),
+            p,
+            p + 1,
+            &self.range,
+            Proof::Byte,
+        ));
+    }
+    /// Pushes a node of the lowest level of the builder.
+    fn push_node(&mut self, mut last0: ProofNode) {
+        for sub_tree in self.state.iter_mut() {
+            match sub_tree.push(&last0) {
+                Some(last1) => last0 = last1,
+                None => return,
+            }
+        }
+        self.state.push(SubTree::new(&last0));
+    }
+    /// Ends the node like `MainTreeAdd::end`, `last0` is the last node of the lowest level.
+    fn end_with(mut self, last0: Option<ProofNode>) -> ProofNode {
+        let last0 = last0.unwrap_or(ProofNode::new([0, 0], 0, 0, &self.range, Proof::Byte));
+        self.state
+            .iter_mut()
+            .fold(last0, |last0, sub_tree| sub_tree.end(last0))
+    }
+    fn end_node(self) -> ProofNode {
+        self.end_with(None)
+    }
+}
+
+/// Builds a proof from stored blocks. Only blocks which overlap the range are hashed, blocks
+/// left of the range are read for their lengths.
+struct StoredProof<'a, T: Forest> {
+    forest: &'a T,
+    range: Range<u64>,
+}
+
+impl<T: Forest> StoredProof<'_, T> {
+    fn digest(&self, key: &U224, start: u64, end: u64) -> ProofNode {
+        ProofNode::new(from_u224(key), start, end, &self.range, Proof::Byte)
+    }
+    fn builder(&self, start: u64) -> ProofBuilder {
+        ProofBuilder::at(self.range.clone(), start)
+    }
+    fn bytes(&self, v: &[u8], start: u64) -> ProofNode {
+        let mut b = self.builder(start);
+        v.iter().for_each(|&c| b.push(c));
+        b.end_node()
+    }
+    fn child(&self, key: &U224, start: u64, depth: usize) -> io::Result<ProofNode> {
+        let v = self
+            .forest
+            .get_block(&ForestNodeId::new(NodeType::Child, key))?;
+        self.node(key, &v, start, depth, false)
+    }
+    /// The node of the block `v` which content starts at `start`.
+    fn node(
+        &self,
+        key: &U224,
+        v: &[u8],
+        start: u64,
+        depth: usize,
+        is_root: bool,
+    ) -> io::Result<ProofNode> {
+        if depth > MAX_DEPTH {
+            return Err(corrupt(key, "too many levels"));
+        }
+        let is_valid = |n: &ProofNode| {
+            if is_root {
+                root(&n.node_id) == *key
+            } else {
+                to_u224(&n.node_id) == Some(*key)
+            }
+        };
+        let result = match Block::parse(v).map_err(|e| corrupt(key, &e.to_string()))? {
+            Block::Data(buf) => {
+                let end = start + buf.len() as u64;
+                if end <= self.range.start && !is_root {
+                    return Ok(self.digest(key, start, end));
+                }
+                self.bytes(buf, start)
+            }
+            Block::Node { tail, children } => {
+                let id = |k| ForestNodeId::new(NodeType::Child, k);
+                let v0 = self.forest.get_block(&id(&children[0]))?;
+                // children of a block are on the same level.
+                let is_data = v0.first() == Some(&DATA);
+                let first = self.node(&children[0], &v0, start, depth + 1, false)?;
+                let mut p = first.end;
+                let mut nodes = Vec::from([first]);
+                for k in &children[1..] {
+                    let node = if p >= self.range.end {
+                        // the length of a node right of the range is not needed.
+                        self.digest(k, self.range.end, self.range.end)
+                    } else if is_data && p >= self.range.start {
+                        self.child(k, p, depth + 1)?
+                    } else if is_data {
+                        let end = p + self.forest.block_len(&id(k))? - 1;
+                        if end <= self.range.start {
+                            self.digest(k, p, end)
+                        } else {
+                            self.child(k, p, depth + 1)?
+                        }
+                    } else {
+                        self.child(k, p, depth + 1)?
+                    };
+                    p = node.end;
+                    nodes.push(node);
+                }
+                let fold = |pushed: &[ProofNode], last0: Option<&ProofNode>| {
+                    let mut b = self.builder(start);
+                    pushed.iter().for_each(|n| b.push_node(n.clone()));
+                    b.end_with(last0.cloned())
+                };
+                if !tail.is_empty() {
+                    // the tail is the last node of the children level, it's added by `end`.
+                    fold(&nodes, Some(&self.bytes(tail, p)))
+                } else {
+                    // the last child either closes the node or it's added by `end`.
+                    let result = fold(&nodes, None);
+                    let (last, pushed) = nodes.split_last().unwrap();
+                    if is_valid(&result) {
+                        result
+                    } else {
+                        fold(pushed, Some(last))
+                    }
+                }
+            }
+        };
+        if !is_valid(&result) {
+            return Err(corrupt(key, DIGEST_MISMATCH));
+        }
+        Ok(result)
+    }
+}
+
+/// Returns proof tokens of the range of the stored content.
+pub fn prove(forest: &impl Forest, d: &U224, range: Range<u64>) -> io::Result<Vec<String>> {
+    let invalid = || BlocksetError::Invalid("invalid range".to_owned()).into();
+    if range.is_empty() {
+        return Err(invalid());
+    }
+    let s = StoredProof {
+        forest,
+        range: range.clone(),
+    };
+    let v = forest.get_block(&ForestNodeId::new(NodeType::Root, d))?;
+    let node = s.node(d, &v, 0, 0, true)?;
+    if node.end < range.end {
+        return Err(invalid());
+    }
+    Ok(tokens(&node))
+}
+
+/// Computes the root and the offset of the excerpt from the proof tokens.
+/// Bytes of the excerpt should be contiguous leaves of the tree.
+pub fn verify<'a>(tokens: impl Iterator<Item = &'a str>, excerpt: &[u8]) -> Option<(U224, u64)> {
+    let mut stack = Vec::default();
+    let mut bytes = excerpt.iter();
+    let mut started = false;
+    let mut offset = 0u64;
+    for t in tokens {
+        match t {
+            "b" => {
+                started = true;
+                stack.push(to_node_id(*bytes.next()?));
+            }
+            "m" => {
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                stack.push(merge(&a, &b));
+            }
+            _ if started => {
+                if bytes.len() > 0 {
+                    return None;
+                }
+                stack.push(from_hex(t)?);
+            }
+            _ => {
+                let (hex, n) = match t.split_once(':') {
+                    Some((hex, n)) => (hex, Some(n.parse::<u64>().ok()?)),
+                    None => (t, None),
+                };
+                let d = from_hex(hex)?;
+                // a node of a hash is longer than 31 bytes.
+                let n = match (to_u224(&d), n) {
+                    (Some(_), Some(n)) if n >= 32 => n,
+                    (None, None) if len(&d).is_multiple_of(8) => (len(&d) / 8) as u64,
+                    _ => return None,
+                };
+                offset = offset.checked_add(n)?;
+                stack.push(d);
+            }
+        }
+    }
+    if !started || bytes.len() > 0 || stack.len() != 1 {
+        return None;
+    }
+    Some((root(&stack[0]), offset))
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{
+        cdt::{main_tree::MainTreeAdd, node_id::root},
+        forest::{mem::MemForest, tree_add::ForestTreeAdd},
+    };
+
+    use super::{tokens, verify, ProofBuilder};
+
+    fn prove(v: &[u8], offset: usize, len: usize) -> Vec<String> {
+        let range = offset as u64..(offset + len) as u64;
+        let mut b = ProofBuilder::at(range.clone(), 0);
+        let mut forest = MemForest::default();
+        let mut t = MainTreeAdd::new(ForestTreeAdd::new(&mut forest));
+        for &c in v {
+            b.push(c);
+            t.push(c).unwrap();
+        }
+        let node = b.end_node();
+        let d = t.end().unwrap().0;
+        assert_eq!(root(&node.node_id), d);
+        let result = tokens(&node);
+        // a proof from stored blocks is the same.
+        assert_eq!(super::prove(&&mut forest, &d, range).unwrap(), result);
+        let excerpt = &v[offset..offset + len];
+        assert_eq!(
+            verify(result.iter().map(|s| s.as_str()), excerpt),
+            Some((d, offset as u64))
+        );
+        result
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let v = "Imagine intercepting messages from extraterrestrials.".repeat(3);
+        let v = v.as_bytes();
+        for offset in 0..v.len() {
+            for len in 1..(v.len() - offset).min(40) {
+                prove(v, offset, len);
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_large() {
+        let v = (0..100_000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect::<Vec<_>>();
+        let tokens = prove(&v, 50_000, 10);
+        // the proof is much smaller than the content.
+        assert!(tokens.len() < 200);
+        for (offset, len) in [
+            (0, 1),
+            (99_999, 1),
+            (1000, 20_000),
+            (0, 100_000),
+            (70_000, 30_000),
+        ] {
+            prove(&v, offset, len);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_stored_invalid() {
+        let v = b"Content-Dependent Hash Tree".repeat(1000);
+        let mut forest = MemForest::default();
+        let mut t = MainTreeAdd::new(ForestTreeAdd::new(&mut forest));
+        v.iter().for_each(|&c| {
+            t.push(c).unwrap();
+        });
+        let d = t.end().unwrap().0;
+        let len = v.len() as u64;
+        assert!(super::prove(&&mut forest, &d, 0..len).is_ok());
+        assert!(super::prove(&&mut forest, &d, 0..len + 1).is_err());
+        assert!(super::prove(&&mut forest, &d, 5..5).is_err());
+        // a corrupt data block.
+        let (_, block) = forest[1].iter_mut().find(|(_, b)| b[0] == 0x20).unwrap();
+        block[5] ^= 1;
+        assert!(super::prove(&&mut forest, &d, 0..len).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid() {
+        let v = b"Content-Dependent Hash Tree";
+        let tokens = prove(v, 3, 4);
+        let t = || tokens.iter().map(|s| s.as_str());
+        let root = verify(t(), &v[3..7]);
+        assert_ne!(verify(t(), b"tenT"), root);
+        assert_eq!(verify(t(), &v[3..6]), None);
+        assert_eq!(verify(t(), &v[3..8]), None);
+        // the excerpt is not contiguous.
+        let tokens = prove(v, 0, v.len());
+        let mut split = tokens.clone();
+        let i = split.iter().position(|s| s == "b").unwrap();
+        split.insert(i + 1, format!("{:064x}", 0));
+        assert_eq!(verify(split.iter().map(|s| s.as_str()), &v[..]), None);
+        assert_eq!(verify(["m"].into_iter(), b""), None);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_offset() {
+        let v = b"Content-Dependent Hash Tree".repeat(100);
+        let tokens = prove(&v, 2000, 10);
+        let i = tokens.iter().position(|s| s.contains(':')).unwrap();
+        let (hex, len) = tokens[i].split_once(':').unwrap();
+        let len: u64 = len.parse().unwrap();
+        let verify_with = |token: String| {
+            let mut t = tokens.clone();
+            t[i] = token;
+            verify(t.iter().map(|s| s.as_str()), &v[2000..2010])
+        };
+        // the length changes the offset only.
+        let (d, offset) = verify_with(format!("{hex}:{}", len + 1)).unwrap();
+        assert_eq!(Some((d, offset - 1)), verify_with(tokens[i].clone()));
+        // a node of a hash needs a length, a node of data can't have it.
+        assert_eq!(verify_with(hex.to_owned()), None);
+        assert_eq!(verify_with(format!("{hex}:31")), None);
+        let data = tokens.iter().position(|s| s.len() == 64 && s != &tokens[i]);
+        if let Some(j) = data.filter(|&j| j < i) {
+            let mut t = tokens.clone();
+            t[j] += ":1";
+            assert_eq!(verify(t.iter().map(|s| s.as_str()), &v[2000..2010]), None);
+        }
+    }
+}
@@ -17,19 +17,38 @@ pub const fn height(&[a0, a1]: &U256, &[b0, b1]: &U256) -> u32 {
     result + v.leading_zeros()
 }
 
+/// A value which can be grouped by `SubTree`.
+pub trait SubTreeNode: Clone {
+    fn node_id(&self) -> &U256;
+    fn merge(&self, b: &Self) -> Self;
+}
+
+impl SubTreeNode for U256 {
+    fn node_id(&self) -> &U256 {
+        self
+    }
+    fn merge(&self, b: &Self) -> Self {
+        merge(self, b)
+    }
+}
+
 #[derive(PartialEq, Debug)]
-struct Node {
-    root: U256,
+struct Node<T> {
+    root: T,
     last: U256,
     height: u32,
 }
 
-impl Node {
-    fn new2(last: &U256, height: u32) -> Self {
-        Self::new3(last, last, height)
+impl<T: SubTreeNode> Node<T> {
+    fn new2(last: &T, height: u32) -> Self {
+        Self::new3(last, last.node_id(), height)
     }
-    fn new3(&root: &U256, &last: &U256, height: u32) -> Self {
-        Node { root, last, height }
+    fn new3(root: &T, &last: &U256, height: u32) -> Self {
+        Node {
+            root: root.clone(),
+            last,
+            height,
+        }
     }
 }
 
@@ -38,21 +57,26 @@ fn group(last1: &U256, last0: &U256) -> bool {
     great(last1, last0)
 }
 
-#[derive(Default)]
-pub struct SubTree(Vec<Node>);
+pub struct SubTree<T = U256>(Vec<Node<T>>);
+
+impl<T> Default for SubTree<T> {
+    fn default() -> Self {
+        Self(Vec::default())
+    }
+}
 
-impl SubTree {
-    pub fn new(last: &U256) -> Self {
+impl<T: SubTreeNode> SubTree<T> {
+    pub fn new(last: &T) -> Self {
         Self([Node::new2(last, 0)].cast())
     }
-    pub fn push(&mut self, last0: &U256) -> Option<U256> {
+    pub fn push(&mut self, last0: &T) -> Option<T> {
         let mut height10 = 0;
         if let Some(mut last1) = self.0.pop() {
             // last0 >= last1.last
-            if !group(&last1.last, last0) {
-                return Some(self.end(merge(&last1.root, last0)));
+            if !group(&last1.last, last0.node_id()) {
+                return Some(self.end(last1.root.merge(last0)));
             }
-            height10 = height(&last1.last, last0);
+            height10 = height(&last1.last, last0.node_id());
             loop {
                 // we need `<=` instead of `<` to handle a case when `height10` and `last1.height` are both zero.
                 if last1.height <= height10 {
@@ -60,7 +84,7 @@ impl SubTree {
                 }
                 let last2 = self.0.pop().unwrap();
                 last1 = Node {
-                    root: merge(&last2.root, &last1.root),
+                    root: last2.root.merge(&last1.root),
                     last: last1.last,
                     height: last2.height,
                 };
@@ -70,9 +94,9 @@ impl SubTree {
         self.0.push(Node::new2(last0, height10));
         None
     }
-    pub fn end(&mut self, mut last0: U256) -> U256 {
+    pub fn end(&mut self, mut last0: T) -> T {
         while let Some(last1) = self.0.pop() {
-            last0 = merge(&last1.root, &last0);
+            last0 = last1.root.merge(&last0);
         }
         last0
     }
@@ -181,13 +205,13 @@ mod test {
         //
         assert_eq!(t.push(&fb), None);
         let ff_fc = merge(&ff_fe, &merge(&fd, &fc));
-        assert_eq!(t.0, [Node::new3(&&ff_fc, &fc, 0), Node::new2(&fb, 253),]);
+        assert_eq!(t.0, [Node::new3(&ff_fc, &fc, 0), Node::new2(&fb, 253),]);
         //
         assert_eq!(t.push(&fa), None);
         assert_eq!(
             t.0,
             [
-                Node::new3(&&ff_fc, &fc, 0),
+                Node::new3(&ff_fc, &fc, 0),
                 Node::new2(&fb, 253),
                 Node::new2(&fa, 255),
             ]
@@ -198,7 +222,7 @@ mod test {
         assert_eq!(
             t.0,
             [
-                Node::new3(&&ff_fc, &fc, 0),
+                Node::new3(&ff_fc, &fc, 0),
                 Node::new3(&fb_fa, &fa, 253),
                 Node::new2(&f9, 254),
             ]
@@ -208,7 +232,7 @@ mod test {
         assert_eq!(
             t.0,
             [
-                Node::new3(&&ff_fc, &fc, 0),
+                Node::new3(&ff_fc, &fc, 0),
                 Node::new3(&fb_fa, &fa, 253),
                 Node::new2(&f9, 254),
                 Node::new2(&f8, 255),
@@ -15,3 +15,147 @@ impl TreeAdd for () {
         Ok(0)
     }
 }
+
+/// Allows reading a state of the sink after hashing.
+impl<T: TreeAdd> TreeAdd for &mut T {
+    fn push(&mut self, node_id: &U256, main_height: usize) -> io::Result<u64> {
+        (**self).push(node_id, main_height)
+    }
+    fn end(&mut self, node_id: &U224, main_height: usize) -> io::Result<u64> {
+        (**self).end(node_id, main_height)
+    }
+}
+
+/// Feeds both sinks and returns the sum of their numbers of new bytes.
+pub struct Tee<A: TreeAdd, B: TreeAdd>(pub A, pub B);
+
+impl<A: TreeAdd, B: TreeAdd> TreeAdd for Tee<A, B> {
+    fn push(&mut self, node_id: &U256, main_height: usize) -> io::Result<u64> {
+        Ok(self.0.push(node_id, main_height)? + self.1.push(node_id, main_height)?)
+    }
+    fn end(&mut self, node_id: &U224, main_height: usize) -> io::Result<u64> {
+        Ok(self.0.end(node_id, main_height)? + self.1.end(node_id, main_height)?)
+    }
+}
+
+/// Counts pushed nodes per `main_height`.
+#[derive(Default)]
+pub struct Stats {
+    pub nodes: Vec<u64>,
+}
+
+impl TreeAdd for Stats {
+    fn push(&mut self, _: &U256, main_height: usize) -> io::Result<u64> {
+        if self.nodes.len() <= main_height {
+            self.nodes.resize(main_height + 1, 0);
+        }
+        self.nodes[main_height] += 1;
+        Ok(0)
+    }
+    fn end(&mut self, _: &U224, _: usize) -> io::Result<u64> {
+        Ok(0)
+    }
+}
+
+/// Passes only nodes at `min_height` and above. The root is always passed.
+/// `ForestTreeAdd` needs all nodes, it returns an error behind `MinHeight`.
+pub struct MinHeight<T: TreeAdd> {
+    pub tree_add: T,
+    pub min_height: usize,
+}
+
+impl<T: TreeAdd> TreeAdd for MinHeight<T> {
+    fn push(&mut self, node_id: &U256, main_height: usize) -> io::Result<u64> {
+        if main_height < self.min_height {
+            return Ok(0);
+        }
+        self.tree_add.push(node_id, main_height)
+    }
+    fn end(&mut self, node_id: &U224, main_height: usize) -> io::Result<u64> {
+        self.tree_add.end(node_id, main_height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{
+        cdt::writer::CdtWriter,
+        forest::{mem::MemForest, tree_add::ForestTreeAdd},
+    };
+
+    use super::{MinHeight, Stats, Tee};
+
+    const S: &str = "The quick brown fox jumps over the lazy dog. ";
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_tee() {
+        let s = S.repeat(100);
+        let mut a = MemForest::default();
+        let mut b = MemForest::default();
+        let mut stats = Stats::default();
+        let mut w = CdtWriter::new(Tee(
+            ForestTreeAdd::new(&mut a),
+            Tee(ForestTreeAdd::new(&mut b), &mut stats),
+        ));
+        w.write_all(s.as_bytes()).unwrap();
+        let (_, new) = w.finish().unwrap();
+        assert!(new > 0);
+        assert_eq!(a, b);
+        // both forests got the same new bytes.
+        let mut c = MemForest::default();
+        let mut w = CdtWriter::new(ForestTreeAdd::new(&mut c));
+        w.write_all(s.as_bytes()).unwrap();
+        assert_eq!(new, 2 * w.finish().unwrap().1);
+        assert_eq!(stats.nodes[0], s.len() as u64);
+        assert!(stats.nodes.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_min_height() {
+        let s = S.repeat(100);
+        let mut all = Stats::default();
+        let mut high = Stats::default();
+        let mut w = CdtWriter::new(Tee(
+            &mut all,
+            MinHeight {
+                tree_add: &mut high,
+                min_height: 2,
+            },
+        ));
+        w.write_all(s.as_bytes()).unwrap();
+        w.finish().unwrap();
+        assert_eq!(high.nodes[..2], [0, 0]);
+        assert_eq!(high.nodes[2..], all.nodes[2..]);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_min_height_forest() {
+        let s = S.repeat(3200);
+        assert!(s.len() > 140_000);
+        let mut forest = MemForest::default();
+        let mut w = CdtWriter::new(MinHeight {
+            tree_add: ForestTreeAdd::new(&mut forest),
+            min_height: 8,
+        });
+        let e = w
+            .write_all(s.as_bytes())
+            .and_then(|_| w.finish().map(|_| ()))
+            .unwrap_err();
+        assert_eq!(e.to_string(), "missing nodes below the data level");
+        assert_eq!(forest, MemForest::default());
+        // a small content has only the root.
+        let mut w = CdtWriter::new(MinHeight {
+            tree_add: ForestTreeAdd::new(&mut forest),
+            min_height: 8,
+        });
+        w.write_all(S.as_bytes()).unwrap();
+        assert!(w.finish().is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use std::io::{self, Write};
+
+use crate::uint::u224::U224;
+
+use super::{main_tree::MainTreeAdd, tree_add::TreeAdd};
+
+/// Hashes and stores everything written into it.
+pub struct CdtWriter<T: TreeAdd> {
+    tree: MainTreeAdd<T>,
+    new: u64,
+}
+
+impl<T: TreeAdd> CdtWriter<T> {
+    pub fn new(tree_add: T) -> Self {
+        Self {
+            tree: MainTreeAdd::new(tree_add),
+            new: 0,
+        }
+    }
+    /// Returns the root hash and the number of new bytes.
+    pub fn finish(mut self) -> io::Result<(U224, u64)> {
+        let (root, new) = self.tree.end()?;
+        Ok((root, self.new + new))
+    }
+}
+
+impl<T: TreeAdd> Write for CdtWriter<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &c in buf {
+            self.new += self.tree.push(c)?;
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{copy, Write};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{
+        cdt::main_tree::MainTreeAdd,
+        forest::{mem::MemForest, tree_add::ForestTreeAdd},
+    };
+
+    use super::CdtWriter;
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let s = "Hello, world!".repeat(100);
+        let mut tree = MainTreeAdd::new(());
+        for c in s.bytes() {
+            tree.push(c).unwrap();
+        }
+        let mut w = CdtWriter::new(());
+        copy(&mut s.as_bytes(), &mut w).unwrap();
+        assert_eq!(w.finish().unwrap(), (tree.end().unwrap().0, 0));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_new() {
+        let mut forest = MemForest::default();
+        let mut write = |s: &str| {
+            let mut w = CdtWriter::new(ForestTreeAdd::new(&mut forest));
+            w.write_all(s.as_bytes()).unwrap();
+            w.finish().unwrap()
+        };
+        let s = "Content-dependent tree. ".repeat(100);
+        let (a, new) = write(&s);
+        assert!(new > 0);
+        assert_eq!(write(&s), (a, 0));
+    }
+}
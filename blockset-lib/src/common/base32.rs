@@ -6,7 +6,7 @@ const fn to_base32(v: u8) -> char {
     b"0123456789abcdefghjkmnpqrstvwxyz"[v as usize & 0x1F] as char
 }
 
-const fn from_base32(x: char) -> Option<u8> {
+pub const fn from_base32(x: char) -> Option<u8> {
     if let Some(mut b) = to_ascii(x) {
         b = b.to_ascii_lowercase();
         Some(match b {
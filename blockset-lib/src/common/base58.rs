@@ -0,0 +1,82 @@
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn from_base58(c: u8) -> Option<u8> {
+    ALPHABET.iter().position(|&x| x == c).map(|i| i as u8)
+}
+
+/// https://datatracker.ietf.org/doc/html/draft-msporny-base58-03
+pub fn to_base58(v: &[u8]) -> String {
+    let zeros = v.iter().take_while(|&&c| c == 0).count();
+    // little-endian base58 digits.
+    let mut digits: Vec<u8> = Vec::default();
+    for &c in &v[zeros..] {
+        let mut carry = c as u32;
+        for d in &mut digits {
+            carry += (*d as u32) << 8;
+            *d = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    "1".repeat(zeros)
+        + &digits
+            .iter()
+            .rev()
+            .map(|&d| ALPHABET[d as usize] as char)
+            .collect::<String>()
+}
+
+pub fn from_base58_str(s: &str) -> Option<Vec<u8>> {
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    // little-endian bytes.
+    let mut bytes: Vec<u8> = Vec::default();
+    for c in s[zeros..].bytes() {
+        let mut carry = from_base58(c)? as u32;
+        for b in &mut bytes {
+            carry += (*b as u32) * 58;
+            *b = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut result = [0].repeat(zeros);
+    result.extend(bytes.iter().rev());
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{from_base58_str, to_base58};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        // https://datatracker.ietf.org/doc/html/draft-msporny-base58-03#section-5
+        let f = |a: &[u8], b: &str| {
+            assert_eq!(to_base58(a), b);
+            assert_eq!(from_base58_str(b).unwrap(), a);
+        };
+        f(b"", "");
+        f(b"Hello World!", "2NEpo7TZRRrLZSi2U");
+        f(
+            b"The quick brown fox jumps over the lazy dog.",
+            "USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z",
+        );
+        f(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], "11233QC4");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid() {
+        assert_eq!(from_base58_str("0"), None);
+        assert_eq!(from_base58_str("Il"), None);
+    }
+}
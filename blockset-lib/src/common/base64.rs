@@ -0,0 +1,94 @@
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const fn from_base64(c: u8) -> Option<u8> {
+    Some(match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - (b'a' - 26),
+        b'0'..=b'9' => c + (52 - b'0'),
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    })
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc4648#section-4
+pub fn to_base64(v: &[u8]) -> String {
+    let mut result = String::with_capacity(v.len().div_ceil(3) * 4);
+    for chunk in v.chunks(3) {
+        let b = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |a, (i, &x)| a | ((x as u32) << (16 - i * 8)));
+        for i in 0..4 {
+            result.push(if i <= chunk.len() {
+                ALPHABET[(b >> (18 - i * 6)) as usize & 0x3F] as char
+            } else {
+                '='
+            });
+        }
+    }
+    result
+}
+
+pub fn from_base64_str(s: &str) -> Option<Vec<u8>> {
+    let s = s.as_bytes();
+    if s.len() & 3 != 0 {
+        return None;
+    }
+    let mut result = Vec::with_capacity(s.len() / 4 * 3);
+    for (ci, chunk) in s.chunks(4).enumerate() {
+        let last = ci == s.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return None;
+        }
+        let mut b = 0;
+        for (i, &c) in chunk[..4 - padding].iter().enumerate() {
+            b |= (from_base64(c)? as u32) << (18 - i * 6);
+        }
+        for i in 0..3 - padding {
+            result.push((b >> (16 - i * 8)) as u8);
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{from_base64_str, to_base64};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        // https://datatracker.ietf.org/doc/html/rfc4648#section-10
+        let f = |a: &str, b: &str| {
+            assert_eq!(to_base64(a.as_bytes()), b);
+            assert_eq!(from_base64_str(b).unwrap(), a.as_bytes());
+        };
+        f("", "");
+        f("f", "Zg==");
+        f("fo", "Zm8=");
+        f("foo", "Zm9v");
+        f("foob", "Zm9vYg==");
+        f("fooba", "Zm9vYmE=");
+        f("foobar", "Zm9vYmFy");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_binary() {
+        let v = (0..=0xFF).collect::<Vec<u8>>();
+        assert_eq!(from_base64_str(&to_base64(&v)).unwrap(), v);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid() {
+        assert_eq!(from_base64_str("Zg="), None);
+        assert_eq!(from_base64_str("Z==="), None);
+        assert_eq!(from_base64_str("Zg==Zg=="), None);
+        assert_eq!(from_base64_str("Z$=="), None);
+    }
+}
@@ -0,0 +1,125 @@
+use super::base64::{from_base64_str, to_base64};
+
+/// https://datatracker.ietf.org/doc/html/rfc2397
+pub const DATA: &str = "data:";
+
+const BASE64: &str = ";base64";
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+// If <mediatype> is omitted, it defaults to text/plain;charset=US-ASCII.
+const DEFAULT_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+
+#[derive(Debug, PartialEq)]
+pub struct DataUri {
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+pub fn is_data_uri(s: &str) -> bool {
+    s.starts_with(DATA)
+}
+
+pub fn to_data_uri(v: &[u8]) -> String {
+    DATA.to_owned() + OCTET_STREAM + BASE64 + "," + &to_base64(v)
+}
+
+const fn hex(c: u8) -> Option<u8> {
+    Some(match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - (b'a' - 10),
+        b'A'..=b'F' => c - (b'A' - 10),
+        _ => return None,
+    })
+}
+
+fn percent_decode(s: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len());
+    let mut i = s.bytes();
+    while let Some(c) = i.next() {
+        result.push(if c == b'%' {
+            (hex(i.next()?)? << 4) | hex(i.next()?)?
+        } else {
+            c
+        });
+    }
+    Some(result)
+}
+
+fn media_type(m: &str) -> String {
+    if m.is_empty() {
+        DEFAULT_MEDIA_TYPE.to_owned()
+    } else if m.starts_with(';') {
+        "text/plain".to_owned() + m
+    } else {
+        m.to_owned()
+    }
+}
+
+pub fn from_data_uri(s: &str) -> Option<DataUri> {
+    let (m, data) = s.strip_prefix(DATA)?.split_once(',')?;
+    let data = percent_decode(data)?;
+    Some(if let Some(m) = m.strip_suffix(BASE64) {
+        DataUri {
+            media_type: media_type(m),
+            data: from_base64_str(core::str::from_utf8(&data).ok()?)?,
+        }
+    } else {
+        DataUri {
+            media_type: media_type(m),
+            data,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{from_data_uri, is_data_uri, to_data_uri, DataUri};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let s = to_data_uri(b"Hello, world!");
+        assert_eq!(
+            s,
+            "data:application/octet-stream;base64,SGVsbG8sIHdvcmxkIQ=="
+        );
+        assert!(is_data_uri(&s));
+        assert_eq!(
+            from_data_uri(&s).unwrap(),
+            DataUri {
+                media_type: "application/octet-stream".to_owned(),
+                data: b"Hello, world!".to_vec()
+            }
+        );
+        assert_eq!(from_data_uri(&to_data_uri(b"")).unwrap().data, b"");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_rfc2397() {
+        let x = from_data_uri("data:,A%20brief%20note").unwrap();
+        assert_eq!(x.media_type, "text/plain;charset=US-ASCII");
+        assert_eq!(x.data, b"A brief note");
+        let x = from_data_uri("data:;charset=utf-8,%F0%9F%A6%80").unwrap();
+        assert_eq!(x.media_type, "text/plain;charset=utf-8");
+        assert_eq!(x.data, "🦀".as_bytes());
+        let x = from_data_uri("data:text/plain;base64,SGVsbG8=").unwrap();
+        assert_eq!(x.media_type, "text/plain");
+        assert_eq!(x.data, b"Hello");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid() {
+        assert!(!is_data_uri(
+            "3v1d4j94scaseqgcyzr0ha5dxa9rx6ppnfbndck971ack"
+        ));
+        assert_eq!(from_data_uri("data:text/plain"), None);
+        assert_eq!(from_data_uri("data:,%2"), None);
+        assert_eq!(from_data_uri("data:,%xy"), None);
+        assert_eq!(from_data_uri("data:;base64,$"), None);
+    }
+}
@@ -0,0 +1,216 @@
+//! Reversible content filters for text files. Binary files are never filtered.
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use super::{eol::ToPosixEol, progress::Progress};
+
+pub const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+pub const NATIVE_EOL: &[u8] = if cfg!(windows) { b"\r\n" } else { b"\n" };
+
+/// A file is binary if its first bytes contain a zero byte.
+const BINARY_PROBE: u64 = 8000;
+
+const UTF8_BOM_NAME: &str = "utf8-bom";
+
+const POSIX_EOL_NAME: &str = "posix-eol";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Filter {
+    /// The UTF-8 BOM is removed.
+    pub utf8_bom: bool,
+    /// CRLF is replaced with LF. Only files whose first bytes have CRLF and no bare LF are
+    /// filtered.
+    pub posix_eol: bool,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        *self == Filter::default()
+    }
+    pub fn names(&self) -> Vec<&'static str> {
+        [
+            (self.utf8_bom, UTF8_BOM_NAME),
+            (self.posix_eol, POSIX_EOL_NAME),
+        ]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect()
+    }
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut result = Filter::default();
+        for name in names {
+            match name {
+                UTF8_BOM_NAME => result.utf8_bom = true,
+                POSIX_EOL_NAME => result.posix_eol = true,
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+    /// Filters which are in both sets.
+    pub fn and(self, other: Filter) -> Filter {
+        Filter {
+            utf8_bom: self.utf8_bom && other.utf8_bom,
+            posix_eol: self.posix_eol && other.posix_eol,
+        }
+    }
+    /// Requested filters which apply to the content with the given first bytes.
+    pub fn detect(self, head: &[u8]) -> Filter {
+        if head.contains(&0) {
+            return Filter::default();
+        }
+        Filter {
+            utf8_bom: self.utf8_bom && head.starts_with(UTF8_BOM),
+            posix_eol: self.posix_eol && is_crlf(head),
+        }
+    }
+}
+
+/// `true` if the bytes have CRLF line endings and no bare LF.
+fn is_crlf(v: &[u8]) -> bool {
+    let mut lf = v
+        .iter()
+        .enumerate()
+        .filter(|&(_, &c)| c == b'\n')
+        .peekable();
+    lf.peek().is_some() && lf.all(|(i, _)| i > 0 && v[i - 1] == b'\r')
+}
+
+pub enum Filtered<R: Read> {
+    Raw(R),
+    PosixEol(ToPosixEol<R>),
+}
+
+impl<R: Read> Read for Filtered<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Filtered::Raw(r) => r.read(buf),
+            Filtered::PosixEol(r) => r.read(buf),
+        }
+    }
+}
+
+impl<R: Read + Progress> Progress for Filtered<R> {
+    fn position(&mut self) -> io::Result<u64> {
+        match self {
+            Filtered::Raw(r) => r.position(),
+            Filtered::PosixEol(r) => r.position(),
+        }
+    }
+}
+
+/// Applies requested filters which fit the content. Returns the applied filters.
+pub fn open<R: Read + Seek>(mut r: R, requested: Filter) -> io::Result<(Filtered<R>, Filter)> {
+    let mut head = Vec::default();
+    (&mut r).take(BINARY_PROBE).read_to_end(&mut head)?;
+    let f = requested.detect(&head);
+    let start = if f.utf8_bom { UTF8_BOM.len() as u64 } else { 0 };
+    r.seek(SeekFrom::Start(start))?;
+    let r = if f.posix_eol {
+        Filtered::PosixEol(ToPosixEol::new(r))
+    } else {
+        Filtered::Raw(r)
+    };
+    Ok((r, f))
+}
+
+/// Reverts filters: writes the UTF-8 BOM and replaces LF with `eol`.
+pub struct Unfilter<W: Write> {
+    w: W,
+    eol: Option<&'static [u8]>,
+}
+
+impl<W: Write> Unfilter<W> {
+    pub fn new(mut w: W, f: Filter, eol: &'static [u8]) -> io::Result<Self> {
+        if f.utf8_bom {
+            w.write_all(UTF8_BOM)?;
+        }
+        Ok(Self {
+            w,
+            eol: f.posix_eol.then_some(eol),
+        })
+    }
+}
+
+impl<W: Write> Write for Unfilter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(eol) = self.eol else {
+            return self.w.write(buf);
+        };
+        for line in buf.split_inclusive(|&c| c == b'\n') {
+            match line.strip_suffix(b"\n") {
+                Some(l) => {
+                    self.w.write_all(l)?;
+                    self.w.write_all(eol)?;
+                }
+                None => self.w.write_all(line)?,
+            }
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.w.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Read, Write};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{open, Filter, Unfilter};
+
+    const ALL: Filter = Filter {
+        utf8_bom: true,
+        posix_eol: true,
+    };
+
+    fn filter(src: &[u8], requested: Filter) -> (Vec<u8>, Filter) {
+        let (mut r, f) = open(Cursor::new(src), requested).unwrap();
+        let mut v = Vec::default();
+        r.read_to_end(&mut v).unwrap();
+        (v, f)
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let src = b"\xEF\xBB\xBFa\r\nb\r\n";
+        let (v, f) = filter(src, ALL);
+        assert_eq!((v.as_slice(), f), (&b"a\nb\n"[..], ALL));
+        let mut w = Unfilter::new(Vec::default(), f, b"\r\n").unwrap();
+        w.write_all(&v[..2]).unwrap();
+        w.write_all(&v[2..]).unwrap();
+        assert_eq!(w.w, src);
+        // no BOM
+        let (v, f) = filter(b"a\r\n", ALL);
+        assert_eq!(
+            (v.as_slice(), f.names()),
+            (&b"a\n"[..], ["posix-eol"].to_vec())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_binary() {
+        let src = b"\xEF\xBB\xBFa\r\n\0";
+        assert_eq!(filter(src, ALL), (src.to_vec(), Filter::default()));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_mixed_eol() {
+        for src in [&b"a\r\nb\n"[..], b"a\nb\r\n", b"a\nb\n", b"a\rb", b"\na"] {
+            assert_eq!(filter(src, ALL), (src.to_vec(), Filter::default()));
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_names() {
+        assert_eq!(Filter::from_names(ALL.names()), Some(ALL));
+        assert_eq!(Filter::from_names(["x"]), None);
+        assert!(Filter::from_names([]).unwrap().is_empty());
+    }
+}
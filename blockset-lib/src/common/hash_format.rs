@@ -0,0 +1,237 @@
+//! Text forms of a hash. Binary forms use the bytes of the hash as they are stored in blocks.
+use crate::uint::{
+    u224::U224,
+    u32::{from_u8x4, to_u8x4},
+};
+
+use super::{
+    base32::{StrEx, ToBase32},
+    base58::{from_base58_str, to_base58},
+};
+
+const URI: &str = "cdt0:";
+
+/// CDT0 has no registered multihash code, so it uses the private use area of the multicodec table.
+const MULTIHASH_CDT0: u64 = 0x30_0000;
+
+/// CIDv1 of raw binary content.
+const CID_V1_RAW: [u8; 2] = [0x01, 0x55];
+
+/// The multibase prefix of base58btc.
+const BASE58BTC: char = 'z';
+
+/// The multibase prefix of lowercase RFC 4648 base32 without padding.
+const BASE32: char = 'b';
+
+const RFC4648: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HashFormat {
+    #[default]
+    Base32,
+    Hex,
+    Multihash,
+    Cid,
+    /// `cdt0:<base32>`.
+    Uri,
+}
+
+impl HashFormat {
+    pub fn from_name(s: &str) -> Option<Self> {
+        Some(match s {
+            "base32" => HashFormat::Base32,
+            "hex" => HashFormat::Hex,
+            "multihash" => HashFormat::Multihash,
+            "cid" => HashFormat::Cid,
+            "uri" => HashFormat::Uri,
+            _ => return None,
+        })
+    }
+    pub fn format(self, d: &U224) -> String {
+        match self {
+            HashFormat::Base32 => d.to_base32(),
+            HashFormat::Hex => to_hex(&to_bytes(d)),
+            HashFormat::Multihash => BASE58BTC.to_string() + &to_base58(&multihash(d)),
+            HashFormat::Cid => {
+                BASE32.to_string() + &to_rfc4648(&[CID_V1_RAW.as_slice(), &multihash(d)].concat())
+            }
+            HashFormat::Uri => URI.to_owned() + &d.to_base32(),
+        }
+    }
+}
+
+/// Parses any form. The forms have different lengths or prefixes.
+pub fn parse_hash(s: &str) -> Option<U224> {
+    if let Some(b32) = s.strip_prefix(URI) {
+        return b32.from_base32();
+    }
+    match s.len() {
+        45 => s.from_base32(),
+        56 => from_hex(s),
+        _ => {
+            if let Some(s) = s.strip_prefix(BASE58BTC) {
+                from_multihash(&from_base58_str(s)?)
+            } else {
+                from_multihash(from_rfc4648(s.strip_prefix(BASE32)?)?.strip_prefix(&CID_V1_RAW)?)
+            }
+        }
+    }
+}
+
+fn to_bytes(d: &U224) -> Vec<u8> {
+    d.iter().copied().flat_map(to_u8x4).collect()
+}
+
+fn from_bytes(v: &[u8]) -> Option<U224> {
+    if v.len() != 28 {
+        return None;
+    }
+    let mut d = U224::default();
+    for (di, c) in d.iter_mut().zip(v.chunks_exact(4)) {
+        *di = from_u8x4(&[c[0], c[1], c[2], c[3]]);
+    }
+    Some(d)
+}
+
+/// Lowercase hex digits of the bytes.
+pub fn to_hex(v: &[u8]) -> String {
+    v.iter()
+        .flat_map(|b| [b >> 4, b & 0xF])
+        .map(|n| char::from(b"0123456789abcdef"[usize::from(n)]))
+        .collect()
+}
+
+fn from_hex(s: &str) -> Option<U224> {
+    if !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let v = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    from_bytes(&v)
+}
+
+fn push_varint(v: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        v.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    v.push(n as u8);
+}
+
+fn read_varint(v: &[u8]) -> Option<(u64, &[u8])> {
+    let mut n = 0;
+    for (i, &b) in v.iter().enumerate().take(9) {
+        n |= ((b & 0x7F) as u64) << (i * 7);
+        if b < 0x80 {
+            return Some((n, &v[i + 1..]));
+        }
+    }
+    None
+}
+
+/// `<code><length><hash>`.
+fn multihash(d: &U224) -> Vec<u8> {
+    let mut v = Vec::default();
+    push_varint(&mut v, MULTIHASH_CDT0);
+    push_varint(&mut v, 28);
+    v.extend(to_bytes(d));
+    v
+}
+
+fn from_multihash(v: &[u8]) -> Option<U224> {
+    let (code, v) = read_varint(v)?;
+    let (len, v) = read_varint(v)?;
+    if code != MULTIHASH_CDT0 || len != v.len() as u64 {
+        return None;
+    }
+    from_bytes(v)
+}
+
+fn to_rfc4648(v: &[u8]) -> String {
+    let mut result = String::default();
+    let mut bits = 0u32;
+    let mut len = 0;
+    for &b in v {
+        bits = (bits << 8) | b as u32;
+        len += 8;
+        while len >= 5 {
+            len -= 5;
+            result.push(RFC4648[(bits >> len) as usize & 0x1F] as char);
+        }
+    }
+    if len > 0 {
+        result.push(RFC4648[(bits << (5 - len)) as usize & 0x1F] as char);
+    }
+    result
+}
+
+fn from_rfc4648(s: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::default();
+    let mut bits = 0u32;
+    let mut len = 0;
+    for c in s.bytes() {
+        let i = RFC4648.iter().position(|&x| x == c)?;
+        bits = (bits << 5) | i as u32;
+        len += 5;
+        if len >= 8 {
+            len -= 8;
+            result.push((bits >> len) as u8);
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{cdt::node_id::root, common::base32::ToBase32};
+
+    use super::{parse_hash, HashFormat};
+
+    const ALL: [&str; 5] = ["base32", "hex", "multihash", "cid", "uri"];
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let d = root(&[0, 0]);
+        for name in ALL {
+            let f = HashFormat::from_name(name).unwrap();
+            let s = f.format(&d);
+            assert_eq!(parse_hash(&s), Some(d), "{s}");
+        }
+        assert_eq!(HashFormat::from_name("x"), None);
+        assert_eq!(
+            HashFormat::Uri.format(&d),
+            "cdt0:".to_owned() + &d.to_base32()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_hex() {
+        let d = [0x0302_0100, 0, 0, 0, 0, 0, 0xFFFF_FFFF];
+        let s = HashFormat::Hex.format(&d);
+        assert_eq!(s, "00010203".to_owned() + &"0".repeat(40) + "ffffffff");
+        assert_eq!(parse_hash(&s), Some(d));
+        assert_eq!(parse_hash(&s.replace('f', "g")), None);
+        assert_eq!(parse_hash(&s[2..]), None);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_multiformats() {
+        let d = [1, 2, 3, 4, 5, 6, 7];
+        let m = HashFormat::Multihash.format(&d);
+        let c = HashFormat::Cid.format(&d);
+        assert!(m.starts_with('z'));
+        assert_ne!(m.len(), 45);
+        assert!(c.starts_with("bafk"));
+        assert_eq!((parse_hash(&m), parse_hash(&c)), (Some(d), Some(d)));
+        assert_eq!(parse_hash(&c[..c.len() - 1]), None);
+        assert_eq!(parse_hash("z"), None);
+        assert_eq!(parse_hash("b"), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! What `run` needs from the platform beyond `Io`.
+use std::io::{self, Write};
+
+use io_trait::Io;
+
+pub trait Host: Io {
+    /// Progress is reported here, so stdout contains only results.
+    type Stderr: Write + 'static;
+    fn stderr(&self) -> Self::Stderr;
+    /// `true` if stderr is a TTY, so progress can be redrawn in place.
+    fn is_stderr_terminal(&self) -> bool;
+    /// Fills the buffer with cryptographically secure random bytes.
+    fn random(&self, buf: &mut [u8]) -> io::Result<()>;
+    /// Makes the file readable and writable by its owner only, where the platform allows it.
+    fn set_owner_only(&self, _path: &str) -> io::Result<()> {
+        Ok(())
+    }
+    fn remove_file(&self, path: &str) -> io::Result<()>;
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::{
+        cell::RefCell,
+        io::{self, Write},
+    };
+
+    use io_test::VirtualIo;
+    use io_trait::Io;
+
+    use super::Host;
+
+    thread_local! {
+        static OWNER_ONLY: RefCell<Vec<String>> = RefCell::default();
+        static REMOVED: RefCell<Vec<String>> = RefCell::default();
+        static STDERR: RefCell<Vec<u8>> = RefCell::default();
+    }
+
+    /// Collects stderr of all `VirtualIo`s of the test thread.
+    pub struct Stderr;
+
+    impl Write for Stderr {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            STDERR.with(|v| v.borrow_mut().extend_from_slice(buf));
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Takes the text which was written to stderr.
+    pub fn take_stderr() -> String {
+        String::from_utf8(STDERR.with(|v| v.take())).unwrap()
+    }
+
+    /// `true` if `set_owner_only` was called for the path.
+    pub fn is_owner_only(path: &str) -> bool {
+        OWNER_ONLY.with(|v| v.borrow().iter().any(|p| p == path))
+    }
+
+    /// `true` if `remove_file` was called for the path. `VirtualIo` can't remove files, so the
+    /// file stays readable.
+    pub fn is_removed(path: &str) -> bool {
+        REMOVED.with(|v| v.borrow().iter().any(|p| p == path))
+    }
+
+    /// `VirtualIo` has no entropy, so the seed of a generated key is always `[7; 32]`.
+    impl Host for VirtualIo {
+        type Stderr = Stderr;
+        fn stderr(&self) -> Stderr {
+            Stderr
+        }
+        fn is_stderr_terminal(&self) -> bool {
+            false
+        }
+        fn random(&self, buf: &mut [u8]) -> io::Result<()> {
+            buf.fill(7);
+            Ok(())
+        }
+        fn set_owner_only(&self, path: &str) -> io::Result<()> {
+            OWNER_ONLY.with(|v| v.borrow_mut().push(path.to_owned()));
+            Ok(())
+        }
+        fn remove_file(&self, path: &str) -> io::Result<()> {
+            self.metadata(path)?;
+            if is_removed(path) {
+                return Err(io::ErrorKind::NotFound.into());
+            }
+            REMOVED.with(|v| v.borrow_mut().push(path.to_owned()));
+            Ok(())
+        }
+    }
+}
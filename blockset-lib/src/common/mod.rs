@@ -1,7 +1,14 @@
 pub mod ascii;
 pub mod base32;
+pub mod base58;
+pub mod base64;
 pub mod bit_vec;
+pub mod data_uri;
 pub mod eol;
+pub mod filter;
+pub mod hash_format;
+pub mod host;
 pub mod print;
 pub mod progress;
+pub mod report;
 pub mod status_line;
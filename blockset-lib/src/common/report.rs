@@ -0,0 +1,188 @@
+//! Progress reporters. All of them write to stderr so stdout contains only results.
+use std::io::{self, Write};
+
+use crate::uint::u64::div_rem;
+
+use super::status_line::mb;
+
+pub struct Status<'a> {
+    /// A text prefix, for example `Processed: 10 MB, `.
+    pub text: &'a str,
+    /// The number of processed bytes.
+    pub bytes: u64,
+    /// From `0.0` to `1.0`.
+    pub p: f64,
+    /// Seconds from the start.
+    pub elapsed: f64,
+}
+
+impl Status<'_> {
+    fn bytes_per_second(&self) -> u64 {
+        if self.elapsed > 0.0 {
+            (self.bytes as f64 / self.elapsed) as u64
+        } else {
+            0
+        }
+    }
+    fn time_left(&self) -> u64 {
+        (self.elapsed * (1.0 - self.p) / self.p) as u64
+    }
+    pub fn line(&self) -> String {
+        let percent = (self.p * 100.0) as u8;
+        self.text.to_owned()
+            + &percent.to_string()
+            + "%. "
+            + &mb(self.bytes_per_second())
+            + "/s. Time left: "
+            + &time(self.time_left())
+            + "."
+    }
+}
+
+fn time(s: u64) -> String {
+    let (h, s) = div_rem(s, 3600);
+    let (m, s) = div_rem(s, 60);
+    format!("{}:{:02}:{:02}", h, m, s)
+}
+
+pub trait Report {
+    /// The minimal time between reports in seconds.
+    fn interval(&self) -> f64;
+    fn report(&mut self, s: &Status) -> io::Result<()>;
+    /// Called when the operation is over.
+    fn end(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Redraws one line with backspaces.
+pub struct Terminal<W: Write> {
+    w: W,
+    prior: usize,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(w: W) -> Self {
+        Self { w, prior: 0 }
+    }
+    fn set(&mut self, s: &str) -> io::Result<()> {
+        let mut vec = Vec::default();
+        vec.resize(self.prior, 8);
+        vec.extend_from_slice(s.as_bytes());
+        if s.len() < self.prior {
+            let len = self.prior - s.len();
+            vec.resize(self.prior * 2, 0x20);
+            vec.resize(vec.len() + len, 8);
+        }
+        self.w.write_all(&vec)?;
+        self.prior = s.len();
+        Ok(())
+    }
+}
+
+impl<W: Write> Report for Terminal<W> {
+    fn interval(&self) -> f64 {
+        0.01
+    }
+    fn report(&mut self, s: &Status) -> io::Result<()> {
+        self.set(&s.line())
+    }
+    fn end(&mut self) -> io::Result<()> {
+        self.set("")
+    }
+}
+
+/// Prints a line from time to time, for logs.
+pub struct Plain<W: Write>(pub W);
+
+impl<W: Write> Report for Plain<W> {
+    fn interval(&self) -> f64 {
+        1.0
+    }
+    fn report(&mut self, s: &Status) -> io::Result<()> {
+        self.0.write_all((s.line() + "\n").as_bytes())
+    }
+}
+
+pub struct Silent;
+
+impl Report for Silent {
+    fn interval(&self) -> f64 {
+        f64::INFINITY
+    }
+    fn report(&mut self, _: &Status) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// JSON lines, `{"event":"progress","bytes":...,"progress":...,"bytes_per_second":...}`.
+pub struct JsonLines<W: Write>(pub W);
+
+impl<W: Write> Report for JsonLines<W> {
+    fn interval(&self) -> f64 {
+        0.1
+    }
+    fn report(&mut self, s: &Status) -> io::Result<()> {
+        // the progress with three decimal digits.
+        let p = (s.p * 1000.0).round() as u64;
+        let line = "{\"event\":\"progress\",\"bytes\":".to_owned()
+            + &s.bytes.to_string()
+            + ",\"progress\":"
+            + &(p / 1000).to_string()
+            + "."
+            + &(1000 + p % 1000).to_string()[1..]
+            + ",\"bytes_per_second\":"
+            + &s.bytes_per_second().to_string()
+            + "}\n";
+        self.0.write_all(line.as_bytes())
+    }
+    fn end(&mut self) -> io::Result<()> {
+        self.0.write_all(b"{\"event\":\"end\"}\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{JsonLines, Plain, Report, Status, Terminal};
+
+    const S: Status = Status {
+        text: "Processed: 3 MB, ",
+        bytes: 3_000_000,
+        p: 0.25,
+        elapsed: 2.0,
+    };
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_plain() {
+        let mut r = Plain(Vec::default());
+        r.report(&S).unwrap();
+        assert_eq!(r.0, b"Processed: 3 MB, 25%. 1 MB/s. Time left: 0:00:06.\n");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_terminal() {
+        let mut r = Terminal::new(Vec::default());
+        r.report(&S).unwrap();
+        r.end().unwrap();
+        let len = S.line().len();
+        assert_eq!(r.w[..len], *S.line().as_bytes());
+        assert_eq!(r.w.len(), len * 4);
+        assert!(r.w[len * 3..].iter().all(|&c| c == 8));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_json_lines() {
+        let mut r = JsonLines(Vec::default());
+        r.report(&S).unwrap();
+        r.end().unwrap();
+        assert_eq!(
+            String::from_utf8(r.0).unwrap(),
+            "{\"event\":\"progress\",\"bytes\":3000000,\"progress\":0.250,\"bytes_per_second\":1500000}\n{\"event\":\"end\"}\n"
+        );
+    }
+}
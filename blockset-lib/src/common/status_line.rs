@@ -1,66 +1,96 @@
-use std::io::{self, Write};
+use std::io;
 
 use io_trait::Io;
 
-use crate::uint::u64::div_rem;
+use super::{
+    host::Host,
+    report::{JsonLines, Plain, Report, Silent, Status, Terminal},
+};
+
+/// How the progress is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusMode {
+    /// `Terminal` if stderr is a TTY, `Plain` otherwise.
+    Auto,
+    /// A line which is redrawn with backspaces.
+    Terminal,
+    /// A line from time to time.
+    Plain,
+    Silent,
+    /// JSON lines, see `JsonLines`.
+    Json,
+}
+
+impl StatusMode {
+    fn report(self, io: &impl Host) -> Box<dyn Report> {
+        match self {
+            StatusMode::Auto if io.is_stderr_terminal() => StatusMode::Terminal.report(io),
+            StatusMode::Auto => StatusMode::Plain.report(io),
+            StatusMode::Terminal => Box::new(Terminal::new(io.stderr())),
+            StatusMode::Plain => Box::new(Plain(io.stderr())),
+            StatusMode::Silent => Box::new(Silent),
+            StatusMode::Json => Box::new(JsonLines(io.stderr())),
+        }
+    }
+}
 
 pub struct StatusLine<'a, T: Io> {
     io: &'a T,
-    prior: usize,
+    report: Box<dyn Report>,
     start_time: T::Instant,
     prior_elapsed: f64,
+    reported: bool,
 }
 
 pub fn mb(b: u64) -> String {
     (b / 1_000_000).to_string() + " MB"
 }
 
-fn time(s: u64) -> String {
-    let (h, s) = div_rem(s, 3600);
-    let (m, s) = div_rem(s, 60);
-    format!("{}:{:02}:{:02}", h, m, s)
+impl<'a, T: Host> StatusLine<'a, T> {
+    pub fn new(io: &'a T, mode: StatusMode) -> Self {
+        Self::with_report(io, mode.report(io))
+    }
 }
 
 impl<'a, T: Io> StatusLine<'a, T> {
-    pub fn new(io: &'a T) -> Self {
+    fn with_report(io: &'a T, report: Box<dyn Report>) -> Self {
         Self {
             io,
-            prior: 0,
+            report,
             start_time: io.now(),
-            prior_elapsed: -1.0,
+            prior_elapsed: 0.0,
+            reported: false,
         }
     }
-    fn set(&mut self, s: &str) -> io::Result<()> {
-        let mut vec = Vec::default();
-        vec.resize(self.prior, 8);
-        vec.extend_from_slice(s.as_bytes());
-        if s.len() < self.prior {
-            let len = self.prior - s.len();
-            vec.resize(self.prior * 2, 0x20);
-            vec.resize(vec.len() + len, 8);
-        }
-        self.io.stdout().write_all(&vec)?;
-        self.prior = s.len();
-        Ok(())
+    /// Reports nothing, so it doesn't need a `Host`.
+    pub fn silent(io: &'a T) -> Self {
+        Self::with_report(io, Box::new(Silent))
     }
-    pub fn set_progress(&mut self, s: &str, p: f64) -> io::Result<()> {
+    /// `s` is a text prefix, `bytes` is the number of processed bytes.
+    pub fn set_progress(&mut self, s: &str, bytes: u64, p: f64) -> io::Result<()> {
         if p == 0.0 {
             return Ok(());
         }
-        let percent = (p * 100.0) as u8;
         let current = self.io.now();
         let elapsed = (current - self.start_time.clone()).as_secs_f64();
-        if elapsed - self.prior_elapsed < 0.01 {
+        if elapsed - self.prior_elapsed < self.report.interval() {
             return Ok(());
         }
         self.prior_elapsed = elapsed;
-        let left = elapsed * (1.0 - p) / p;
-        let r = s.to_owned() + &percent.to_string() + "%. Time left: " + &time(left as u64) + ".";
-        self.set(&r)
+        self.reported = true;
+        self.report.report(&Status {
+            text: s,
+            bytes,
+            p,
+            elapsed,
+        })
     }
 }
+
 impl<'a, T: Io> Drop for StatusLine<'a, T> {
     fn drop(&mut self) {
-        let _ = self.set("");
+        if self.reported {
+            let _ = self.report.end();
+        }
     }
 }
@@ -0,0 +1,210 @@
+/// An element of GF(2^255 - 19) as 16 limbs of 16 bits.
+pub type Fe = [i64; 16];
+
+pub const ZERO: Fe = [0; 16];
+
+pub const ONE: Fe = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// `-121665 / 121666`
+pub const D: Fe = [
+    0x78a3, 0x1359, 0x4dca, 0x75eb, 0xd8ab, 0x4141, 0x0a4d, 0x0070, 0xe898, 0x7779, 0x4079, 0x8cc7,
+    0xfe73, 0x2b6f, 0x6cee, 0x5203,
+];
+
+/// `2 * D`
+pub const D2: Fe = [
+    0xf159, 0x26b2, 0x9b94, 0xebd6, 0xb156, 0x8283, 0x149a, 0x00e0, 0xd130, 0xeef3, 0x80f2, 0x198e,
+    0xfce7, 0x56df, 0xd9dc, 0x2406,
+];
+
+/// `sqrt(-1)`
+pub const I: Fe = [
+    0xa0b0, 0x4a0e, 0x1b27, 0xc4ee, 0xe478, 0xad2f, 0x1806, 0x2f43, 0xd7a7, 0x3dfb, 0x0099, 0x2b4d,
+    0xdf0b, 0x4fc1, 0x2480, 0x2b83,
+];
+
+const fn carry(mut o: Fe) -> Fe {
+    let mut i = 0;
+    while i < 16 {
+        o[i] += 1 << 16;
+        let c = o[i] >> 16;
+        if i < 15 {
+            o[i + 1] += c - 1;
+        } else {
+            o[0] += 38 * (c - 1);
+        }
+        o[i] -= c << 16;
+        i += 1;
+    }
+    o
+}
+
+/// Swaps `p` and `q` if `b` is `1`, in constant time.
+pub const fn swap(mut p: Fe, mut q: Fe, b: i64) -> (Fe, Fe) {
+    let c = !(b - 1);
+    let mut i = 0;
+    while i < 16 {
+        let t = c & (p[i] ^ q[i]);
+        p[i] ^= t;
+        q[i] ^= t;
+        i += 1;
+    }
+    (p, q)
+}
+
+pub const fn pack(n: &Fe) -> [u8; 32] {
+    let mut t = carry(carry(carry(*n)));
+    let mut j = 0;
+    while j < 2 {
+        let mut m = ZERO;
+        m[0] = t[0] - 0xffed;
+        let mut i = 1;
+        while i < 15 {
+            m[i] = t[i] - 0xffff - ((m[i - 1] >> 16) & 1);
+            m[i - 1] &= 0xffff;
+            i += 1;
+        }
+        m[15] = t[15] - 0x7fff - ((m[14] >> 16) & 1);
+        let b = (m[15] >> 16) & 1;
+        m[14] &= 0xffff;
+        (t, _) = swap(t, m, 1 - b);
+        j += 1;
+    }
+    let mut result = [0; 32];
+    let mut i = 0;
+    while i < 16 {
+        result[2 * i] = t[i] as u8;
+        result[2 * i + 1] = (t[i] >> 8) as u8;
+        i += 1;
+    }
+    result
+}
+
+pub const fn unpack(n: &[u8; 32]) -> Fe {
+    let mut result = ZERO;
+    let mut i = 0;
+    while i < 16 {
+        result[i] = n[2 * i] as i64 + ((n[2 * i + 1] as i64) << 8);
+        i += 1;
+    }
+    result[15] &= 0x7fff;
+    result
+}
+
+pub const fn eq(a: &Fe, b: &Fe) -> bool {
+    let (a, b) = (pack(a), pack(b));
+    let mut d = 0;
+    let mut i = 0;
+    while i < 32 {
+        d |= a[i] ^ b[i];
+        i += 1;
+    }
+    d == 0
+}
+
+pub const fn parity(a: &Fe) -> u8 {
+    pack(a)[0] & 1
+}
+
+pub const fn add(a: &Fe, b: &Fe) -> Fe {
+    let mut result = ZERO;
+    let mut i = 0;
+    while i < 16 {
+        result[i] = a[i] + b[i];
+        i += 1;
+    }
+    result
+}
+
+pub const fn sub(a: &Fe, b: &Fe) -> Fe {
+    let mut result = ZERO;
+    let mut i = 0;
+    while i < 16 {
+        result[i] = a[i] - b[i];
+        i += 1;
+    }
+    result
+}
+
+pub const fn mul(a: &Fe, b: &Fe) -> Fe {
+    let mut t = [0; 31];
+    let mut i = 0;
+    while i < 16 {
+        let mut j = 0;
+        while j < 16 {
+            t[i + j] += a[i] * b[j];
+            j += 1;
+        }
+        i += 1;
+    }
+    i = 0;
+    while i < 15 {
+        t[i] += 38 * t[i + 16];
+        i += 1;
+    }
+    let mut result = ZERO;
+    i = 0;
+    while i < 16 {
+        result[i] = t[i];
+        i += 1;
+    }
+    carry(carry(result))
+}
+
+pub const fn sqr(a: &Fe) -> Fe {
+    mul(a, a)
+}
+
+/// `a^(p - 2)`
+pub const fn inv(a: &Fe) -> Fe {
+    let mut c = *a;
+    let mut i = 253;
+    loop {
+        c = sqr(&c);
+        if i != 2 && i != 4 {
+            c = mul(&c, a);
+        }
+        if i == 0 {
+            return c;
+        }
+        i -= 1;
+    }
+}
+
+/// `a^((p - 5) / 8)`
+pub const fn pow2523(a: &Fe) -> Fe {
+    let mut c = *a;
+    let mut i = 250;
+    loop {
+        c = sqr(&c);
+        if i != 1 {
+            c = mul(&c, a);
+        }
+        if i == 0 {
+            return c;
+        }
+        i -= 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{add, eq, inv, mul, pack, sqr, sub, unpack, D, D2, I, ONE, ZERO};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        assert!(eq(&add(&D, &D), &D2));
+        assert!(eq(&sqr(&I), &sub(&ZERO, &ONE)));
+        assert!(eq(&mul(&D, &inv(&D)), &ONE));
+        let x = pack(&D);
+        assert_eq!(pack(&unpack(&x)), x);
+        // p + 1 == 1
+        let mut p1 = [0xFF; 32];
+        p1[0] = 0xEE;
+        p1[31] = 0x7F;
+        assert_eq!(pack(&unpack(&p1)), pack(&ONE));
+    }
+}
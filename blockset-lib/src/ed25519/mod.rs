@@ -0,0 +1,132 @@
+mod field;
+mod point;
+mod scalar;
+
+use crate::sha2::sha512::sha512;
+
+use self::{
+    point::{scalar_base, scalar_mult, unpack_neg},
+    scalar::{is_canonical, mul_add, reduce},
+};
+
+pub type SecretKey = [u8; 32];
+
+pub type PublicKey = [u8; 32];
+
+pub type Signature = [u8; 64];
+
+fn hash(v: &[&[u8]]) -> [u8; 64] {
+    sha512(&v.concat())
+}
+
+fn expand(sk: &SecretKey) -> ([u8; 32], [u8; 32]) {
+    let h = hash(&[sk]);
+    let mut a = [0; 32];
+    a.copy_from_slice(&h[..32]);
+    a[0] &= 248;
+    a[31] &= 127;
+    a[31] |= 64;
+    let mut prefix = [0; 32];
+    prefix.copy_from_slice(&h[32..]);
+    (a, prefix)
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc8032#section-5.1.5
+pub fn public_key(sk: &SecretKey) -> PublicKey {
+    point::pack(&scalar_base(&expand(sk).0))
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc8032#section-5.1.6
+pub fn sign(sk: &SecretKey, m: &[u8]) -> Signature {
+    let (a, prefix) = expand(sk);
+    let pk = point::pack(&scalar_base(&a));
+    let r = reduce(&hash(&[&prefix, m]));
+    let rp = point::pack(&scalar_base(&r));
+    let k = reduce(&hash(&[&rp, &pk, m]));
+    let s = mul_add(&k, &a, &r);
+    let mut result = [0; 64];
+    result[..32].copy_from_slice(&rp);
+    result[32..].copy_from_slice(&s);
+    result
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc8032#section-5.1.7
+pub fn verify(pk: &PublicKey, m: &[u8], sig: &Signature) -> bool {
+    let mut rp = [0; 32];
+    rp.copy_from_slice(&sig[..32]);
+    let mut s = [0; 32];
+    s.copy_from_slice(&sig[32..]);
+    if !is_canonical(&s) {
+        return false;
+    }
+    let Some(neg_a) = unpack_neg(pk) else {
+        return false;
+    };
+    let k = reduce(&hash(&[&rp, pk, m]));
+    // [S]B - [k]A == R
+    let p = point::add(&scalar_mult(&neg_a, &k), &scalar_base(&s));
+    point::pack(&p) == rp
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{public_key, sign, verify};
+
+    fn from_hex<const N: usize>(s: &str) -> [u8; N] {
+        let mut result = [0; N];
+        for (i, r) in result.iter_mut().enumerate() {
+            *r = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        result
+    }
+
+    // https://datatracker.ietf.org/doc/html/rfc8032#section-7.1
+    fn check(sk: &str, pk: &str, m: &[u8], sig: &str) {
+        let sk = from_hex(sk);
+        let pk = from_hex(pk);
+        let sig = from_hex(sig);
+        assert_eq!(public_key(&sk), pk);
+        assert_eq!(sign(&sk, m), sig);
+        assert!(verify(&pk, m, &sig));
+        let mut m1 = m.to_vec();
+        m1.push(0);
+        assert!(!verify(&pk, &m1, &sig));
+        let mut sig1 = sig;
+        sig1[63] ^= 0x10;
+        assert!(!verify(&pk, m, &sig1));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test1() {
+        check(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            b"",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test2() {
+        check(
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            &[0x72],
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid_key() {
+        let sig = sign(&[1; 32], b"x");
+        // `y = 2` is not on the curve.
+        let mut pk = [0; 32];
+        pk[0] = 2;
+        assert!(!verify(&pk, b"x", &sig));
+    }
+}
@@ -0,0 +1,100 @@
+use super::field::{self, Fe, D, D2, I, ONE, ZERO};
+
+/// A point in extended coordinates `(X, Y, Z, T)`, where `x = X / Z`, `y = Y / Z` and `x * y = T / Z`.
+pub type Point = [Fe; 4];
+
+const BASE_X: Fe = [
+    0xd51a, 0x8f25, 0x2d60, 0xc956, 0xa7b2, 0x9525, 0xc760, 0x692c, 0xdc5c, 0xfdd6, 0xe231, 0xc0a4,
+    0x53fe, 0xcd6e, 0x36d3, 0x2169,
+];
+
+const BASE_Y: Fe = [
+    0x6658, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666,
+    0x6666, 0x6666, 0x6666, 0x6666,
+];
+
+const BASE: Point = [BASE_X, BASE_Y, ONE, field::mul(&BASE_X, &BASE_Y)];
+
+const NEUTRAL: Point = [ZERO, ONE, ONE, ZERO];
+
+pub const fn add(p: &Point, q: &Point) -> Point {
+    let a = field::mul(&field::sub(&p[1], &p[0]), &field::sub(&q[1], &q[0]));
+    let b = field::mul(&field::add(&p[0], &p[1]), &field::add(&q[0], &q[1]));
+    let c = field::mul(&field::mul(&p[3], &q[3]), &D2);
+    let d = field::mul(&p[2], &q[2]);
+    let d = field::add(&d, &d);
+    let e = field::sub(&b, &a);
+    let f = field::sub(&d, &c);
+    let g = field::add(&d, &c);
+    let h = field::add(&b, &a);
+    [
+        field::mul(&e, &f),
+        field::mul(&h, &g),
+        field::mul(&g, &f),
+        field::mul(&e, &h),
+    ]
+}
+
+const fn swap(mut p: Point, mut q: Point, b: i64) -> (Point, Point) {
+    let mut i = 0;
+    while i < 4 {
+        (p[i], q[i]) = field::swap(p[i], q[i], b);
+        i += 1;
+    }
+    (p, q)
+}
+
+/// `s * q`, where `s` is a little-endian scalar.
+pub const fn scalar_mult(q: &Point, s: &[u8; 32]) -> Point {
+    let mut p = NEUTRAL;
+    let mut q = *q;
+    let mut i = 256;
+    while i > 0 {
+        i -= 1;
+        let b = ((s[i >> 3] >> (i & 7)) & 1) as i64;
+        (p, q) = swap(p, q, b);
+        q = add(&q, &p);
+        p = add(&p, &p);
+        (p, q) = swap(p, q, b);
+    }
+    p
+}
+
+pub const fn scalar_base(s: &[u8; 32]) -> Point {
+    scalar_mult(&BASE, s)
+}
+
+pub const fn pack(p: &Point) -> [u8; 32] {
+    let zi = field::inv(&p[2]);
+    let x = field::mul(&p[0], &zi);
+    let y = field::mul(&p[1], &zi);
+    let mut result = field::pack(&y);
+    result[31] ^= field::parity(&x) << 7;
+    result
+}
+
+/// Decodes a point and negates it. Returns `None` if `v` is not a valid point.
+pub const fn unpack_neg(v: &[u8; 32]) -> Option<Point> {
+    let y = field::unpack(v);
+    let num = field::sqr(&y);
+    let den = field::mul(&num, &D);
+    let num = field::sub(&num, &ONE);
+    let den = field::add(&ONE, &den);
+    let den2 = field::sqr(&den);
+    let den4 = field::sqr(&den2);
+    let den6 = field::mul(&den4, &den2);
+    let mut t = field::mul(&field::mul(&den6, &num), &den);
+    t = field::pow2523(&t);
+    t = field::mul(&field::mul(&field::mul(&t, &num), &den), &den);
+    let mut x = field::mul(&t, &den);
+    if !field::eq(&field::mul(&field::sqr(&x), &den), &num) {
+        x = field::mul(&x, &I);
+    }
+    if !field::eq(&field::mul(&field::sqr(&x), &den), &num) {
+        return None;
+    }
+    if field::parity(&x) == v[31] >> 7 {
+        x = field::sub(&ZERO, &x);
+    }
+    Some([x, y, ONE, field::mul(&x, &y)])
+}
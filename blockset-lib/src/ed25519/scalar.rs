@@ -0,0 +1,112 @@
+/// The order of the base point, `2^252 + 27742317777372353535851937790883648493`.
+const L: [i64; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+];
+
+/// `x mod L`, where `x` is a little-endian number with 64 limbs.
+pub const fn mod_l(mut x: [i64; 64]) -> [u8; 32] {
+    let mut i = 63;
+    while i >= 32 {
+        let mut carry = 0;
+        let mut j = i - 32;
+        while j < i - 12 {
+            x[j] += carry - 16 * x[i] * L[j - (i - 32)];
+            carry = (x[j] + 128) >> 8;
+            x[j] -= carry << 8;
+            j += 1;
+        }
+        x[j] += carry;
+        x[i] = 0;
+        i -= 1;
+    }
+    let mut carry = 0;
+    let mut j = 0;
+    while j < 32 {
+        x[j] += carry - (x[31] >> 4) * L[j];
+        carry = x[j] >> 8;
+        x[j] &= 0xFF;
+        j += 1;
+    }
+    j = 0;
+    while j < 32 {
+        x[j] -= carry * L[j];
+        j += 1;
+    }
+    let mut result = [0; 32];
+    i = 0;
+    while i < 32 {
+        x[i + 1] += x[i] >> 8;
+        result[i] = x[i] as u8;
+        i += 1;
+    }
+    result
+}
+
+pub const fn reduce(v: &[u8; 64]) -> [u8; 32] {
+    let mut x = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        x[i] = v[i] as i64;
+        i += 1;
+    }
+    mod_l(x)
+}
+
+/// `(a * b + c) mod L`
+pub const fn mul_add(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let mut x = [0; 64];
+    let mut i = 0;
+    while i < 32 {
+        x[i] = c[i] as i64;
+        i += 1;
+    }
+    i = 0;
+    while i < 32 {
+        let mut j = 0;
+        while j < 32 {
+            x[i + j] += a[i] as i64 * b[j] as i64;
+            j += 1;
+        }
+        i += 1;
+    }
+    mod_l(x)
+}
+
+/// Returns `true` if `s < L`.
+pub const fn is_canonical(s: &[u8; 32]) -> bool {
+    let mut i = 32;
+    while i > 0 {
+        i -= 1;
+        let (a, b) = (s[i] as i64, L[i]);
+        if a != b {
+            return a < b;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{is_canonical, mul_add, reduce, L};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let l = L.map(|x| x as u8);
+        assert!(!is_canonical(&l));
+        let mut l1 = l;
+        l1[0] -= 1;
+        assert!(is_canonical(&l1));
+        // L mod L == 0
+        let mut x = [0; 64];
+        x[..32].copy_from_slice(&l);
+        assert_eq!(reduce(&x), [0; 32]);
+        // (L - 1) * (L - 1) + 0 == 1 mod L
+        let mut one = [0; 32];
+        one[0] = 1;
+        assert_eq!(mul_add(&l1, &l1, &[0; 32]), one);
+    }
+}
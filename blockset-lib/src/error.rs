@@ -0,0 +1,134 @@
+use std::{error::Error, fmt, io};
+
+use crate::{common::base32::ToBase32, uint::u224::U224};
+
+/// An error which is carried inside `io::Error`, see `exit_code`.
+#[derive(Debug)]
+pub enum BlocksetError {
+    /// Invalid command line arguments.
+    Usage(String),
+    /// A string is not a valid hash.
+    InvalidHash(String),
+    /// The block is not in the repository.
+    MissingBlock(U224),
+    /// The block can't be decoded.
+    CorruptBlock(U224, String),
+    /// The block is not a valid directory block.
+    DirectoryFormat(U224, String),
+    /// A file system error with the path of the file.
+    Io(String, io::Error),
+    /// Invalid input data or a failed verification.
+    Invalid(String),
+    /// A path in a directory block or an entry in a log is not found.
+    NotFound(String),
+    /// Both sides of a merge changed the same paths.
+    MergeConflict(String),
+}
+
+impl BlocksetError {
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            BlocksetError::Invalid(_) => 1,
+            BlocksetError::Usage(_) => 2,
+            BlocksetError::InvalidHash(_) => 3,
+            BlocksetError::MissingBlock(_) => 4,
+            BlocksetError::CorruptBlock(..) => 5,
+            BlocksetError::DirectoryFormat(..) => 6,
+            BlocksetError::Io(..) => 7,
+            BlocksetError::NotFound(_) => 8,
+            BlocksetError::MergeConflict(_) => 9,
+        }
+    }
+}
+
+impl fmt::Display for BlocksetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlocksetError::Usage(s)
+            | BlocksetError::Invalid(s)
+            | BlocksetError::NotFound(s)
+            | BlocksetError::MergeConflict(s) => f.write_str(s),
+            BlocksetError::InvalidHash(_) => f.write_str("invalid hash"),
+            BlocksetError::MissingBlock(d) => {
+                f.write_str(&("block not found: ".to_owned() + &d.to_base32()))
+            }
+            BlocksetError::CorruptBlock(d, s) => {
+                f.write_str(&("corrupt block ".to_owned() + &d.to_base32() + ": " + s))
+            }
+            BlocksetError::DirectoryFormat(d, s) => {
+                f.write_str(&("invalid directory block ".to_owned() + &d.to_base32() + ": " + s))
+            }
+            BlocksetError::Io(path, e) => f.write_str(&(path.to_owned() + ": " + &e.to_string())),
+        }
+    }
+}
+
+impl Error for BlocksetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BlocksetError::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<BlocksetError> for io::Error {
+    fn from(e: BlocksetError) -> Self {
+        let kind = match &e {
+            BlocksetError::MissingBlock(_) | BlocksetError::NotFound(_) => io::ErrorKind::NotFound,
+            BlocksetError::CorruptBlock(..) | BlocksetError::DirectoryFormat(..) => {
+                io::ErrorKind::InvalidData
+            }
+            BlocksetError::Io(_, e) => e.kind(),
+            _ => io::ErrorKind::InvalidInput,
+        };
+        io::Error::new(kind, e)
+    }
+}
+
+/// Returns the `BlocksetError` inside `io::Error`.
+pub fn blockset_error(e: &io::Error) -> Option<&BlocksetError> {
+    e.get_ref()?.downcast_ref()
+}
+
+/// A process exit code. Other `io::Error`s are I/O errors.
+pub fn exit_code(e: &io::Error) -> i32 {
+    blockset_error(e).map_or(7, BlocksetError::exit_code)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{exit_code, BlocksetError};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let e: io::Error = BlocksetError::MissingBlock([0; 7]).into();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+        assert_eq!(exit_code(&e), 4);
+        assert_eq!(
+            e.to_string(),
+            "block not found: ".to_owned() + &"0".repeat(45)
+        );
+        let e: io::Error =
+            BlocksetError::Io("a.txt".to_owned(), io::ErrorKind::NotFound.into()).into();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+        assert_eq!(exit_code(&e), 7);
+        assert!(e.to_string().starts_with("a.txt: "));
+        assert_eq!(exit_code(&io::ErrorKind::Other.into()), 7);
+        let e: io::Error = BlocksetError::Usage("missing hash".to_owned()).into();
+        assert_eq!((exit_code(&e), e.to_string().as_str()), (2, "missing hash"));
+        let e: io::Error = BlocksetError::CorruptBlock([0; 7], "x".to_owned()).into();
+        assert_eq!(
+            e.to_string(),
+            "corrupt block ".to_owned() + &"0".repeat(45) + ": x"
+        );
+        let e: io::Error = BlocksetError::NotFound("path not found: a".to_owned()).into();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+        assert_eq!(exit_code(&e), 8);
+    }
+}
@@ -0,0 +1,104 @@
+//! Decoding of stored blocks. Blocks may come from untrusted mirrors, so decoding never panics.
+use std::{error::Error, fmt};
+
+use crate::uint::{u224::U224, u32::from_u8x4};
+
+/// The first byte of a data block.
+pub const DATA: u8 = 0x20;
+
+const KEY_LEN: usize = 28;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Block<'a> {
+    /// A part of the content.
+    Data(&'a [u8]),
+    /// Keys of child blocks in the content order and data which ends the content.
+    Node { tail: &'a [u8], children: Vec<U224> },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlockError {
+    Empty,
+    /// The block is shorter than the tail length in its first byte.
+    Truncated,
+    /// Child keys are not a multiple of 28 bytes.
+    KeyLength,
+    NoChildren,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BlockError::Empty => "empty block",
+            BlockError::Truncated => "truncated block",
+            BlockError::KeyLength => "invalid block length",
+            BlockError::NoChildren => "no child blocks",
+        })
+    }
+}
+
+impl Error for BlockError {}
+
+fn key(v: &[u8]) -> U224 {
+    let mut k = U224::default();
+    for (ki, c) in k.iter_mut().zip(v.chunks_exact(4)) {
+        *ki = from_u8x4(&[c[0], c[1], c[2], c[3]]);
+    }
+    k
+}
+
+impl<'a> Block<'a> {
+    pub fn parse(v: &'a [u8]) -> Result<Self, BlockError> {
+        let (&len, v) = v.split_first().ok_or(BlockError::Empty)?;
+        if len == DATA {
+            return Ok(Block::Data(v));
+        }
+        if v.len() < len as usize {
+            return Err(BlockError::Truncated);
+        }
+        let (tail, keys) = v.split_at(len as usize);
+        if keys.is_empty() {
+            return Err(BlockError::NoChildren);
+        }
+        let chunks = keys.chunks_exact(KEY_LEN);
+        if !chunks.remainder().is_empty() {
+            return Err(BlockError::KeyLength);
+        }
+        Ok(Block::Node {
+            tail,
+            children: chunks.map(key).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::uint::u32::to_u8x4;
+
+    use super::{Block, BlockError};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        assert_eq!(Block::parse(b" Hello"), Ok(Block::Data(b"Hello")));
+        let a = [1, 2, 3, 4, 5, 6, 7];
+        let b = [8, 9, 10, 11, 12, 13, 14];
+        let v = [2, b'a', b'b']
+            .into_iter()
+            .chain([a, b].into_iter().flatten().flat_map(to_u8x4))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Block::parse(&v),
+            Ok(Block::Node {
+                tail: b"ab",
+                children: [a, b].to_vec()
+            })
+        );
+        assert_eq!(Block::parse(&v[..v.len() - 1]), Err(BlockError::KeyLength));
+        assert_eq!(Block::parse(&v[..3]), Err(BlockError::NoChildren));
+        assert_eq!(Block::parse(&v[..2]), Err(BlockError::Truncated));
+        assert_eq!(Block::parse(&[]), Err(BlockError::Empty));
+    }
+}
@@ -1,8 +1,17 @@
 use std::io;
 
-use io_trait::Io;
-
-use crate::{cdt::node_type::NodeType, common::base32::ToBase32, forest::Forest};
+use io_trait::{DirEntry, Io, Metadata};
+
+use crate::{
+    cdt::node_type::NodeType,
+    common::{
+        base32::{StrEx, ToBase32},
+        host::Host,
+    },
+    error::BlocksetError,
+    forest::{Forest, ForestDelete},
+    uint::u224::U224,
+};
 
 use super::node_id::ForestNodeId;
 
@@ -23,19 +32,80 @@ fn path(id: &ForestNodeId) -> String {
     CDT0.to_owned() + "/" + dir(id.node_type) + "/" + &s[..2] + "/" + &s[2..4] + "/" + &s[4..]
 }
 
+/// A missing file is a missing block, other errors keep the path.
+fn block_error(id: &ForestNodeId, p: String) -> impl FnOnce(io::Error) -> io::Error + '_ {
+    move |e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            BlocksetError::MissingBlock(id.hash)
+        } else {
+            BlocksetError::Io(p, e)
+        }
+        .into()
+    }
+}
+
+/// Sorted names of subdirectories or files.
+fn names(io: &impl Io, path: &str, is_dir: bool) -> Vec<String> {
+    let mut result = io
+        .read_dir_type(path, is_dir)
+        .unwrap_or_default()
+        .iter()
+        .map(|e| {
+            let path = e.path();
+            path.rsplit(['/', '\\'])
+                .next()
+                .unwrap_or_default()
+                .to_owned()
+        })
+        .collect::<Vec<_>>();
+    result.sort();
+    result
+}
+
 impl<'a, T: Io> Forest for FileForest<'a, T> {
     fn has_block(&self, id: &ForestNodeId) -> bool {
         self.0.metadata(&path(id)).is_ok()
     }
 
     fn get_block(&self, id: &ForestNodeId) -> io::Result<Vec<u8>> {
-        self.0.read(&path(id))
+        let p = path(id);
+        self.0.read(&p).map_err(block_error(id, p))
     }
 
     fn set_block(&mut self, id: &ForestNodeId, value: impl Iterator<Item = u8>) -> io::Result<()> {
         let x = value.collect::<Vec<_>>();
         let p = path(id);
-        self.0.write_recursively(&p, &x)
+        self.0
+            .write_recursively(&p, &x)
+            .map_err(|e| BlocksetError::Io(p, e).into())
+    }
+
+    fn block_len(&self, id: &ForestNodeId) -> io::Result<u64> {
+        let p = path(id);
+        Ok(self.0.metadata(&p).map_err(block_error(id, p))?.len())
+    }
+
+    fn node_ids(&self, node_type: NodeType) -> impl Iterator<Item = ForestNodeId> + '_ {
+        let io = self.0;
+        let d = CDT0.to_owned() + "/" + dir(node_type);
+        names(io, &d, true).into_iter().flat_map(move |a| {
+            let da = d.clone() + "/" + &a;
+            names(io, &da, true).into_iter().flat_map(move |b| {
+                let prefix = a.clone() + &b;
+                names(io, &(da.clone() + "/" + &b), false)
+                    .into_iter()
+                    .filter_map(move |c| (prefix.clone() + &c).from_base32::<U224>())
+                    .map(move |h| ForestNodeId::new(node_type, &h))
+            })
+        })
+    }
+}
+
+/// `Io` can't remove files, so it needs a `Host`.
+impl<T: Host> ForestDelete for FileForest<'_, T> {
+    fn delete_block(&mut self, id: &ForestNodeId) -> io::Result<()> {
+        let p = path(id);
+        self.0.remove_file(&p).map_err(block_error(id, p))
     }
 }
 
@@ -43,9 +113,19 @@ impl<'a, T: Io> Forest for FileForest<'a, T> {
 mod test {
     use wasm_bindgen_test::wasm_bindgen_test;
 
-    use crate::{cdt::node_type::NodeType, forest::node_id::ForestNodeId};
+    use std::io::{self, Write};
+
+    use io_test::VirtualIo;
+    use io_trait::Io;
 
-    use super::path;
+    use crate::{
+        cdt::{node_type::NodeType, writer::CdtWriter},
+        common::{base32::ToBase32, host::test::is_removed},
+        error::exit_code,
+        forest::{node_id::ForestNodeId, tree_add::ForestTreeAdd, Forest, ForestDelete},
+    };
+
+    use super::{path, FileForest};
 
     #[wasm_bindgen_test]
     #[test]
@@ -55,4 +135,35 @@ mod test {
         ];
         path(&ForestNodeId::new(NodeType::Root, &k));
     }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_node_ids() {
+        let io = VirtualIo::new(&[]);
+        let mut f = FileForest(&io);
+        let ids = ["Hello", "world"].map(|s| {
+            let mut w = CdtWriter::new(ForestTreeAdd::new(FileForest(&io)));
+            w.write_all(s.as_bytes()).unwrap();
+            ForestNodeId::new(NodeType::Root, &w.finish().unwrap().0)
+        });
+        // not a block.
+        io.write_recursively("cdt0/roots/ab", b"").unwrap();
+        let mut expected = ids.to_vec();
+        expected.sort_by_key(|id| id.hash.to_base32());
+        assert_eq!(f.node_ids(NodeType::Root).collect::<Vec<_>>(), expected);
+        assert_eq!(f.node_ids(NodeType::Child).count(), 0);
+        assert_eq!(f.block_len(&ids[0]).unwrap(), 6);
+        let missing = ForestNodeId::new(NodeType::Child, &ids[0].hash);
+        assert_eq!(f.has_blocks(&[ids[1], missing]), [true, false]);
+        let e = f.block_len(&missing).unwrap_err();
+        assert_eq!((exit_code(&e), e.kind()), (4, io::ErrorKind::NotFound));
+        f.delete_block(&ids[1]).unwrap();
+        assert!(is_removed(&path(&ids[1])));
+        let e = f.delete_block(&ids[1]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "block not found: ".to_owned() + &ids[1].hash.to_base32()
+        );
+        assert!(f.delete_block(&missing).is_err());
+    }
 }
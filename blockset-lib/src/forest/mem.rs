@@ -1,9 +1,9 @@
 #![cfg(test)]
 use std::{collections::BTreeMap, io};
 
-use crate::uint::u224::U224;
+use crate::{cdt::node_type::NodeType, common::base32::ToBase32, uint::u224::U224};
 
-use super::{node_id::ForestNodeId, Forest};
+use super::{node_id::ForestNodeId, Forest, ForestDelete};
 
 pub type MemForest = [BTreeMap<U224, Vec<u8>>; 2];
 
@@ -23,4 +23,24 @@ impl Forest for &mut MemForest {
         self[id.node_type as usize].insert(id.hash, value.collect());
         Ok(())
     }
+
+    fn block_len(&self, id: &ForestNodeId) -> io::Result<u64> {
+        Ok(self.get_block(id)?.len() as u64)
+    }
+
+    fn node_ids(&self, node_type: NodeType) -> impl Iterator<Item = ForestNodeId> + '_ {
+        let mut v = self[node_type as usize]
+            .keys()
+            .map(|h| (h.to_base32(), ForestNodeId::new(node_type, h)))
+            .collect::<Vec<_>>();
+        v.sort_by(|a, b| a.0.cmp(&b.0));
+        v.into_iter().map(|(_, id)| id)
+    }
+}
+
+impl ForestDelete for &mut MemForest {
+    fn delete_block(&mut self, id: &ForestNodeId) -> io::Result<()> {
+        self[id.node_type as usize].remove(&id.hash);
+        Ok(())
+    }
 }
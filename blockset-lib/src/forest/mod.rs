@@ -2,37 +2,57 @@ use std::io::{self, Write};
 
 use crate::{
     cdt::{node_id::root, node_type::NodeType},
-    uint::{u224::U224, u32::from_u8x4},
+    error::BlocksetError,
+    uint::u224::U224,
 };
 
-use self::node_id::ForestNodeId;
+use self::{block::Block, node_id::ForestNodeId, verify::Verify};
 
+pub mod block;
 pub mod file;
 pub mod mem;
 pub mod node_id;
 pub mod tree_add;
+mod verify;
 
 const EMPTY: U224 = root(&[0, 0]);
 
-fn get_len(v: &[u8]) -> Option<usize> {
-    let len = *v.first().unwrap();
-    if len == 0x20 {
-        None
-    } else {
-        Some(len as usize + 1)
-    }
+/// Restored blocks are children of at most this number of blocks.
+/// It stops cycles in corrupt blocks.
+pub const MAX_DEPTH: usize = 64;
+
+pub const DIGEST_MISMATCH: &str = "digest mismatch";
+
+pub fn corrupt(key: &U224, reason: &str) -> io::Error {
+    BlocksetError::CorruptBlock(*key, reason.to_owned()).into()
+}
+
+enum Step {
+    /// A block to restore with its share of the content and its depth.
+    Block(U224, f64, usize),
+    /// The content of the block is written.
+    End(U224),
 }
 
-fn get_size(v: &[u8], len: usize, size: f64) -> (usize, f64) {
-    let i = v.len();
-    assert_eq!((i - len) % 28, 0);
-    (i, size / ((i - len) / 28) as f64)
+impl Step {
+    fn is_block(&self) -> bool {
+        match self {
+            Step::Block(..) => true,
+            Step::End(_) => false,
+        }
+    }
 }
 
 pub trait Forest {
     fn has_block(&self, id: &ForestNodeId) -> bool;
     fn get_block(&self, id: &ForestNodeId) -> io::Result<Vec<u8>>;
     fn set_block(&mut self, id: &ForestNodeId, value: impl Iterator<Item = u8>) -> io::Result<()>;
+    fn block_len(&self, id: &ForestNodeId) -> io::Result<u64>;
+    /// All blocks of the node type, ordered by the base32 form of their hashes.
+    fn node_ids(&self, node_type: NodeType) -> impl Iterator<Item = ForestNodeId> + '_;
+    fn has_blocks(&self, ids: &[ForestNodeId]) -> Vec<bool> {
+        ids.iter().map(|id| self.has_block(id)).collect()
+    }
     fn check_set_block(
         &mut self,
         id: &ForestNodeId,
@@ -45,6 +65,8 @@ pub trait Forest {
         Ok(true)
     }
     // we should extract a state machine from the function and remove `set_progress`.
+    /// Writes the content to `w` while it is verified, so after a `CorruptBlock` error `w` has a
+    /// part of the content which may be corrupt.
     fn restore(
         &self,
         id: &ForestNodeId,
@@ -55,48 +77,68 @@ pub trait Forest {
             return Ok(0);
         }
         let mut tail = Vec::default();
-        let mut keys = [(id.hash, 1.0)].to_vec();
+        let mut steps = Vec::from([Step::Block(id.hash, 1.0, 0)]);
+        let mut verify = Verify::default();
+        let mut deferred = Vec::default();
         let mut progress_p = 0.0;
         let mut progress_b = 0;
         let mut t = id.node_type;
         progress(0, 0.0)?;
-        fn push_keys(
-            len: usize,
-            (mut i, size): (usize, f64),
-            v: &[u8],
-            keys: &mut Vec<([u32; 7], f64)>,
-        ) {
-            while len + 28 <= i {
-                let mut kn = U224::default();
-                i -= 28;
-                let mut j = i;
-                for ki in &mut kn {
-                    let n = j + 4;
-                    let slice = &v[j..n];
-                    *ki = from_u8x4(slice.try_into().unwrap());
-                    j = n;
+        while let Some(step) = steps.pop() {
+            let (key, size, depth) = match step {
+                Step::Block(key, size, depth) => (key, size, depth),
+                Step::End(key) => {
+                    if !verify.check(&key) {
+                        if steps.iter().any(Step::is_block) {
+                            return Err(corrupt(&key, DIGEST_MISMATCH));
+                        }
+                        // the last nodes are closed by `Verify::end`.
+                        deferred.push(key);
+                    }
+                    continue;
                 }
-                keys.push((kn, size));
+            };
+            if depth > MAX_DEPTH {
+                return Err(corrupt(&key, "too many levels"));
             }
-        }
-        while let Some((key, size)) = keys.pop() {
             let v = self.get_block(&ForestNodeId::new(t, &key))?;
-            if let Some(len) = get_len(&v) {
-                if len > 1 {
-                    //assert!(tail.is_empty());
-                    tail = v[1..len].to_vec();
+            if t == NodeType::Child {
+                steps.push(Step::End(key));
+            }
+            match Block::parse(&v).map_err(|e| corrupt(&key, &e.to_string()))? {
+                Block::Node { tail: t, children } => {
+                    if !t.is_empty() {
+                        //assert!(tail.is_empty());
+                        tail = t.to_vec();
+                    }
+                    let size = size / children.len() as f64;
+                    let children = children.into_iter().rev();
+                    steps.extend(children.map(|k| Step::Block(k, size, depth + 1)));
+                }
+                Block::Data(buf) => {
+                    w.write_all(buf)?;
+                    verify.push(buf)?;
+                    progress_p += size;
+                    progress_b += buf.len() as u64;
+                    progress(progress_b, progress_p)?;
                 }
-                push_keys(len, get_size(&v, len, size), &v, &mut keys);
-            } else {
-                let buf = &v[1..];
-                w.write_all(buf)?;
-                progress_p += size;
-                progress_b += buf.len() as u64;
-                progress(progress_b, progress_p)?;
             }
             t = NodeType::Child;
         }
         w.write_all(&tail)?;
+        verify.push(&tail)?;
+        let root = verify.end()?;
+        if let Some(key) = deferred.into_iter().find(|key| !verify.check(key)) {
+            return Err(corrupt(&key, DIGEST_MISMATCH));
+        }
+        if id.node_type == NodeType::Root && root != id.hash {
+            return Err(corrupt(&id.hash, DIGEST_MISMATCH));
+        }
         Ok(progress_b)
     }
 }
+
+/// A forest which can remove blocks, for example, to collect garbage.
+pub trait ForestDelete: Forest {
+    fn delete_block(&mut self, id: &ForestNodeId) -> io::Result<()>;
+}
@@ -1,5 +1,6 @@
 use crate::{cdt::node_type::NodeType, uint::u224::U224};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ForestNodeId {
     pub node_type: NodeType,
     pub hash: U224,
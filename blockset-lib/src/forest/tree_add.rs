@@ -32,6 +32,19 @@ struct Levels {
 const DATA_LEVEL: usize = 8;
 const SKIP_LEVEL: usize = 4;
 
+/// Data blocks are made of level 0 nodes, so `ForestTreeAdd` can't work behind `MinHeight`.
+fn missing_data() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "missing nodes below the data level",
+    )
+}
+
+/// Levels of nodes which are stored as blocks, every `SKIP_LEVEL` from `DATA_LEVEL`.
+pub const fn is_stored_level(i: usize) -> bool {
+    i >= DATA_LEVEL && i % SKIP_LEVEL == DATA_LEVEL % SKIP_LEVEL
+}
+
 impl Levels {
     fn store(&mut self, forest: &mut impl Forest, id: &ForestNodeId, i: usize) -> io::Result<u64> {
         let data = take(&mut self.data);
@@ -92,6 +105,9 @@ impl<T: Forest> TreeAdd for ForestTreeAdd<T> {
             }
             return Ok(0);
         }
+        if i == DATA_LEVEL && self.levels.data.is_empty() {
+            return Err(missing_data());
+        }
         i -= DATA_LEVEL;
         if i % SKIP_LEVEL != 0 {
             return Ok(0);
@@ -122,6 +138,9 @@ impl<T: Forest> TreeAdd for ForestTreeAdd<T> {
             return Ok(0);
         }
         i = if i <= DATA_LEVEL {
+            if self.levels.data.is_empty() {
+                return Err(missing_data());
+            }
             0
         } else {
             (i - DATA_LEVEL + SKIP_LEVEL - 1) / SKIP_LEVEL
@@ -133,7 +152,7 @@ impl<T: Forest> TreeAdd for ForestTreeAdd<T> {
 
 #[cfg(test)]
 mod test {
-    use std::io::Cursor;
+    use std::io::{self, Cursor};
 
     use io_test::VirtualIo;
     use nanvm_lib::common::default::default;
@@ -141,9 +160,10 @@ mod test {
 
     use crate::{
         cdt::{main_tree::MainTreeAdd, node_type::NodeType, tree_add::TreeAdd},
-        common::status_line::{mb, StatusLine},
+        common::status_line::{mb, StatusLine, StatusMode},
+        error::{blockset_error, BlocksetError},
         forest::{mem::MemForest, node_id::ForestNodeId, Forest},
-        uint::u224::U224,
+        uint::{u224::U224, u32::to_u8x4},
     };
 
     use super::ForestTreeAdd;
@@ -175,12 +195,14 @@ mod test {
         let mut v = Vec::default();
         let mut cursor = Cursor::new(&mut v);
         let io = VirtualIo::new(&[]);
-        let mut state = StatusLine::new(&io);
+        let mut state = StatusLine::new(&io, StatusMode::Silent);
         table
             .restore(
                 &ForestNodeId::new(NodeType::Root, &k),
                 &mut cursor,
-                |progress_b, progress_p| state.set_progress(&(mb(progress_b) + ", "), progress_p),
+                |progress_b, progress_p| {
+                    state.set_progress(&(mb(progress_b) + ", "), progress_b, progress_p)
+                },
             )
             .unwrap();
         assert_eq!(v, c.as_bytes());
@@ -260,4 +282,113 @@ mod test {
             accept only one argument, and currying is a way to provide multiple
             arguments to a function."#);
     }
+
+    fn restore(table: &mut MemForest, k: &U224) -> io::Result<Vec<u8>> {
+        let mut v = Vec::default();
+        table.restore(&ForestNodeId::new(NodeType::Root, k), &mut v, |_, _| Ok(()))?;
+        Ok(v)
+    }
+
+    fn corrupt_key(e: &io::Error) -> U224 {
+        match blockset_error(e) {
+            Some(BlocksetError::CorruptBlock(k, _)) => *k,
+            _ => panic!("{e}"),
+        }
+    }
+
+    fn random(len: usize, alphabet: u32) -> String {
+        let mut x = 1u32;
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(1664525).wrapping_add(1013904223);
+                char::from(b'a' + ((x >> 24) % alphabet) as u8)
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_verify() {
+        let contents = [
+            random(100_000, 26),
+            random(100_000, 2),
+            "a".repeat(100_000),
+            "The quick brown fox jumps over the lazy dog. ".repeat(1000),
+        ];
+        for c in contents {
+            let table = &mut default();
+            let k = add(table, &c);
+            assert_eq!(restore(table, &k).unwrap(), c.as_bytes());
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_corrupt_data() {
+        let table: &mut MemForest = &mut default();
+        let k = add(table, &random(10_000, 26));
+        let children = &mut table[NodeType::Child as usize];
+        let (&bad, v) = children.iter_mut().find(|(_, v)| v[0] == 0x20).unwrap();
+        v[1] ^= 1;
+        let e = restore(table, &k).unwrap_err();
+        assert_eq!(corrupt_key(&e), bad);
+        assert!(e.to_string().ends_with(": digest mismatch"));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_corrupt_node() {
+        let table: &mut MemForest = &mut default();
+        let k = add(table, &random(100_000, 26));
+        let children = &mut table[NodeType::Child as usize];
+        let (&bad, v) = children.iter_mut().find(|(_, v)| v[0] != 0x20).unwrap();
+        // swap the first two child keys.
+        let (a, b) = v[1..57].split_at_mut(28);
+        a.swap_with_slice(b);
+        assert_eq!(corrupt_key(&restore(table, &k).unwrap_err()), bad);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_corrupt_root() {
+        let table: &mut MemForest = &mut default();
+        let k = add(table, "Hello, world!");
+        let root = table[NodeType::Root as usize].get_mut(&k).unwrap();
+        root.push(b'!');
+        assert_eq!(corrupt_key(&restore(table, &k).unwrap_err()), k);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_malformed() {
+        let table: &mut MemForest = &mut default();
+        let k = add(table, &random(10_000, 26));
+        let blocks = [
+            Vec::default(),
+            [1, 2, 3].to_vec(),
+            [0].into_iter()
+                .chain(k.into_iter().flat_map(to_u8x4))
+                .collect(),
+        ];
+        let root = NodeType::Root as usize;
+        for (block, reason) in blocks.into_iter().zip(["empty", "length", "levels"]) {
+            table[root].insert(k, block.clone());
+            table[NodeType::Child as usize].insert(k, block);
+            let e = restore(table, &k).unwrap_err();
+            assert_eq!(corrupt_key(&e), k);
+            assert!(e.to_string().contains(reason), "{e}");
+        }
+    }
+
+    /// A node block which is shorter than its tail length used to panic.
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_truncated() {
+        let table: &mut MemForest = &mut default();
+        let k = add(table, "Hello, world!");
+        table[NodeType::Root as usize].insert(k, [5].to_vec());
+        let e = restore(table, &k).unwrap_err();
+        assert_eq!(corrupt_key(&e), k);
+        assert!(e.to_string().ends_with(": truncated block"), "{e}");
+    }
 }
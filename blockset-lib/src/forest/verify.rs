@@ -0,0 +1,57 @@
+//! Re-derives keys of restored blocks from the restored content.
+use std::{collections::BTreeSet, io};
+
+use crate::{
+    cdt::{main_tree::MainTreeAdd, tree_add::TreeAdd},
+    uint::{
+        u224::U224,
+        u256::{to_u224, U256},
+    },
+};
+
+use super::tree_add::is_stored_level;
+
+/// Keys of blocks which `ForestTreeAdd` would store.
+#[derive(Default)]
+struct Keys(BTreeSet<U224>);
+
+impl TreeAdd for Keys {
+    fn push(&mut self, digest: &U256, i: usize) -> io::Result<u64> {
+        if is_stored_level(i) {
+            if let Some(k) = to_u224(digest) {
+                self.0.insert(k);
+            }
+        }
+        Ok(0)
+    }
+    fn end(&mut self, _: &U224, _: usize) -> io::Result<u64> {
+        Ok(0)
+    }
+}
+
+/// A node is closed by its last byte, so the key of a block is known as soon as
+/// the content of the block is written. Only the last nodes are closed by `end`.
+pub struct Verify(MainTreeAdd<Keys>);
+
+impl Default for Verify {
+    fn default() -> Self {
+        Self(MainTreeAdd::new(Keys::default()))
+    }
+}
+
+impl Verify {
+    pub fn push(&mut self, buf: &[u8]) -> io::Result<()> {
+        for &c in buf {
+            self.0.push(c)?;
+        }
+        Ok(())
+    }
+    /// Returns `true` if the content written so far has a node with the key.
+    pub fn check(&mut self, key: &U224) -> bool {
+        self.0.tree_add().0.remove(key)
+    }
+    /// Returns the root key of the content.
+    pub fn end(&mut self) -> io::Result<U224> {
+        Ok(self.0.end()?.0)
+    }
+}
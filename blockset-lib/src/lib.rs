@@ -1,9 +1,41 @@
+//! BLOCKSET: content-dependent tree (CDT) hashing and a `cdt0` block repository.
+//!
+//! `Repository` and `Hash` are the entry points for embedding. `MainTreeAdd`,
+//! `TreeAdd` and `Forest` are lower-level extension points.
 mod app;
+mod asn1;
 mod cdt;
 mod common;
+mod ed25519;
+mod error;
 mod forest;
 mod info;
+mod p256;
+mod repository;
+mod rsa;
 mod sha2;
+mod tlog;
+mod tsp;
 mod uint;
 
-pub use app::run;
+/// Only for fuzz targets, which are built by `cargo fuzz` with `--cfg fuzzing`.
+#[cfg(fuzzing)]
+pub use app::get::parse_dir_block;
+pub use app::{add::Entry, run};
+pub use cdt::{
+    main_tree::MainTreeAdd,
+    node_type::NodeType,
+    tree_add::{MinHeight, Stats, Tee, TreeAdd},
+    writer::CdtWriter,
+};
+pub use common::{filter::Filter, hash_format::HashFormat, host::Host};
+pub use error::{blockset_error, exit_code, BlocksetError};
+pub use forest::{
+    block::{Block, BlockError},
+    file::FileForest,
+    node_id::ForestNodeId,
+    tree_add::ForestTreeAdd,
+    Forest, ForestDelete,
+};
+pub use repository::{hash, Hash, Repository};
+pub use uint::{u224::U224, u256::U256};
@@ -0,0 +1,270 @@
+//! ECDSA P-256 verification, https://www.secg.org/sec1-v2.pdf#section.4.1.4
+use crate::uint::mont::{from_be_bytes, is_zero, less, Limbs, Mont};
+
+const P: [u64; 4] = [
+    0xffff_ffff_ffff_ffff,
+    0x0000_0000_ffff_ffff,
+    0x0000_0000_0000_0000,
+    0xffff_ffff_0000_0001,
+];
+
+/// The order of the base point.
+const N: [u64; 4] = [
+    0xf3b9_cac2_fc63_2551,
+    0xbce6_faad_a717_9e84,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_0000_0000,
+];
+
+const B: [u64; 4] = [
+    0x3bce_3c3e_27d2_604b,
+    0x651d_06b0_cc53_b0f6,
+    0xb3eb_bd55_7698_86bc,
+    0x5ac6_35d8_aa3a_93e7,
+];
+
+const GX: [u64; 4] = [
+    0xf4a1_3945_d898_c296,
+    0x7703_7d81_2deb_33a0,
+    0xf8bc_e6e5_63a4_40f2,
+    0x6b17_d1f2_e12c_4247,
+];
+
+const GY: [u64; 4] = [
+    0xcbb6_4068_37bf_51f5,
+    0x2bce_3357_6b31_5ece,
+    0x8ee7_eb4a_7c0f_9e16,
+    0x4fe3_42e2_fe1a_7f9b,
+];
+
+const ZERO: [u64; 4] = [0; 4];
+
+/// `m - 2` for the inversion by the Fermat's little theorem. The low limbs of `P` and `N` don't
+/// borrow.
+const fn minus_two(mut m: [u64; 4]) -> [u64; 4] {
+    m[0] -= 2;
+    m
+}
+
+/// Jacobian coordinates in the Montgomery form, `z == 0` is the point at infinity.
+#[derive(Clone)]
+struct Point {
+    x: Limbs,
+    y: Limbs,
+    z: Limbs,
+}
+
+struct Curve {
+    p: Mont,
+}
+
+impl Curve {
+    fn new() -> Self {
+        Self {
+            p: Mont::new(P.to_vec()).unwrap(),
+        }
+    }
+    fn affine(&self, x: &[u64], y: &[u64]) -> Point {
+        Point {
+            x: self.p.to_mont(x),
+            y: self.p.to_mont(y),
+            z: self.p.one(),
+        }
+    }
+    /// `y^2 == x^3 - 3x + b`
+    fn is_on_curve(&self, x: &[u64], y: &[u64]) -> bool {
+        let f = &self.p;
+        let (x, y) = (f.to_mont(x), f.to_mont(y));
+        let x3 = f.mul(&f.mul(&x, &x), &x);
+        let three_x = f.add(&f.add(&x, &x), &x);
+        f.mul(&y, &y) == f.add(&f.sub(&x3, &three_x), &f.to_mont(&B))
+    }
+    /// https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#doubling-dbl-2001-b
+    fn double(&self, a: &Point) -> Point {
+        let f = &self.p;
+        if is_zero(&a.z) || is_zero(&a.y) {
+            return Point {
+                z: ZERO.to_vec(),
+                ..a.clone()
+            };
+        }
+        let delta = f.mul(&a.z, &a.z);
+        let gamma = f.mul(&a.y, &a.y);
+        let beta = f.mul(&a.x, &gamma);
+        let t = f.mul(&f.sub(&a.x, &delta), &f.add(&a.x, &delta));
+        let alpha = f.add(&f.add(&t, &t), &t);
+        let beta2 = f.add(&beta, &beta);
+        let beta4 = f.add(&beta2, &beta2);
+        let x = f.sub(&f.mul(&alpha, &alpha), &f.add(&beta4, &beta4));
+        let yz = f.add(&a.y, &a.z);
+        let z = f.sub(&f.sub(&f.mul(&yz, &yz), &gamma), &delta);
+        let gamma2 = f.mul(&gamma, &gamma);
+        let gamma4 = f.add(&gamma2, &gamma2);
+        let gamma8 = f.add(&gamma4, &gamma4);
+        let y = f.sub(&f.mul(&alpha, &f.sub(&beta4, &x)), &f.add(&gamma8, &gamma8));
+        Point { x, y, z }
+    }
+    /// https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#addition-add-1998-cmo-2
+    fn add(&self, a: &Point, b: &Point) -> Point {
+        let f = &self.p;
+        if is_zero(&a.z) {
+            return b.clone();
+        }
+        if is_zero(&b.z) {
+            return a.clone();
+        }
+        let z1z1 = f.mul(&a.z, &a.z);
+        let z2z2 = f.mul(&b.z, &b.z);
+        let u1 = f.mul(&a.x, &z2z2);
+        let u2 = f.mul(&b.x, &z1z1);
+        let s1 = f.mul(&f.mul(&a.y, &b.z), &z2z2);
+        let s2 = f.mul(&f.mul(&b.y, &a.z), &z1z1);
+        let h = f.sub(&u2, &u1);
+        let r = f.sub(&s2, &s1);
+        if is_zero(&h) {
+            return if is_zero(&r) {
+                self.double(a)
+            } else {
+                Point {
+                    z: ZERO.to_vec(),
+                    ..a.clone()
+                }
+            };
+        }
+        let hh = f.mul(&h, &h);
+        let hhh = f.mul(&h, &hh);
+        let v = f.mul(&u1, &hh);
+        let x = f.sub(&f.sub(&f.mul(&r, &r), &hhh), &f.add(&v, &v));
+        let y = f.sub(&f.mul(&r, &f.sub(&v, &x)), &f.mul(&s1, &hhh));
+        let z = f.mul(&f.mul(&a.z, &b.z), &h);
+        Point { x, y, z }
+    }
+    /// `u1 * a + u2 * b` by the Shamir's trick.
+    fn mul_add(&self, u1: &[u64], a: &Point, u2: &[u64], b: &Point) -> Point {
+        let ab = self.add(a, b);
+        let mut result = Point {
+            z: ZERO.to_vec(),
+            ..a.clone()
+        };
+        for i in (0..256).rev() {
+            result = self.double(&result);
+            let bit = |u: &[u64]| (u[i / 64] >> (i % 64)) & 1 != 0;
+            let t = match (bit(u1), bit(u2)) {
+                (true, true) => &ab,
+                (true, false) => a,
+                (false, true) => b,
+                (false, false) => continue,
+            };
+            result = self.add(&result, t);
+        }
+        result
+    }
+}
+
+/// An integer in `[1, N - 1]` from the content of a DER `INTEGER`.
+fn scalar(v: &[u8]) -> Option<Limbs> {
+    // negative numbers
+    if v.first().is_some_and(|&b| b & 0x80 != 0) {
+        return None;
+    }
+    let x = from_be_bytes(v, 4)?;
+    if is_zero(&x) || !less(&x, &N) {
+        return None;
+    }
+    Some(x)
+}
+
+pub struct PublicKey {
+    x: Limbs,
+    y: Limbs,
+}
+
+impl PublicKey {
+    /// An uncompressed point, `04 || x || y`.
+    pub fn parse(v: &[u8]) -> Option<Self> {
+        let (4, xy) = v.split_first()? else {
+            return None;
+        };
+        if xy.len() != 64 {
+            return None;
+        }
+        let (x, y) = (from_be_bytes(&xy[..32], 4)?, from_be_bytes(&xy[32..], 4)?);
+        if !less(&x, &P) || !less(&y, &P) || !Curve::new().is_on_curve(&x, &y) {
+            return None;
+        }
+        Some(Self { x, y })
+    }
+    /// `r` and `s` are contents of DER `INTEGER`s.
+    pub fn verify(&self, digest: &[u8; 32], r: &[u8], s: &[u8]) -> bool {
+        let (Some(r), Some(s)) = (scalar(r), scalar(s)) else {
+            return false;
+        };
+        let n = Mont::new(N.to_vec()).unwrap();
+        // the digest is less than `2 * N`, so the addition reduces it.
+        let e = n.add(&from_be_bytes(digest, 4).unwrap(), &ZERO);
+        // `s^-1` in the Montgomery form.
+        let w = n.pow(&n.to_mont(&s), &minus_two(N));
+        let (u1, u2) = (n.mul(&e, &w), n.mul(&r, &w));
+        let c = Curve::new();
+        let q = c.mul_add(&u1, &c.affine(&GX, &GY), &u2, &c.affine(&self.x, &self.y));
+        if is_zero(&q.z) {
+            return false;
+        }
+        let f = &c.p;
+        let z2 = f.mul(&q.z, &q.z);
+        let x = f.to_normal(&f.mul(&q.x, &f.pow(&z2, &minus_two(P))));
+        n.add(&x, &ZERO) == r
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{common::base64::from_base64_str, sha2::sha256::sha256};
+
+    use super::PublicKey;
+
+    /// A public key from `openssl ecparam -name prime256v1 -genkey`.
+    const KEY: &str = concat!(
+        "BLwIWpc89b8sSU37U46yfYRALF9/cBOoGrFz4kzOValb99OzGMXRTD6VDvgIjLPhtvjMXebxD3Ld",
+        "ntGmvgc0/Hc=",
+    );
+
+    /// `openssl dgst -sha256 -sign` of `MESSAGE`, `SEQUENCE { INTEGER r, INTEGER s }`.
+    const SIGNATURE: &str = concat!(
+        "MEUCIENWrlkokWWVO9hDkgOFSe805+t5Vk39AvNd0oAHaa0NAiEA6tiMxoYDKGi5Vmn+KPnhmU9c",
+        "A3/4/tnByWEn9kzuJT0=",
+    );
+
+    const MESSAGE: &[u8] = b"Hello, world!";
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let k = PublicKey::parse(&from_base64_str(KEY).unwrap()).unwrap();
+        let s = from_base64_str(SIGNATURE).unwrap();
+        let (r, s) = (&s[4..36], &s[38..]);
+        let d = sha256(MESSAGE);
+        assert!(k.verify(&d, r, s));
+        assert!(!k.verify(&sha256(b"Hello, world?"), r, s));
+        assert!(!k.verify(&d, s, r));
+        let mut t = r.to_vec();
+        t[31] ^= 1;
+        assert!(!k.verify(&d, &t, s));
+        // a negative `s`
+        assert!(!k.verify(&d, r, &s[1..]));
+        assert!(!k.verify(&d, &[], s));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid_key() {
+        let mut k = from_base64_str(KEY).unwrap();
+        assert!(PublicKey::parse(&k[1..]).is_none());
+        k[64] ^= 1;
+        assert!(PublicKey::parse(&k).is_none());
+        k[0] = 2;
+        assert!(PublicKey::parse(&k).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+//! A library API over a `cdt0` repository.
+use std::{
+    fmt,
+    io::{self, copy, Cursor, Read, Write},
+    str::FromStr,
+};
+
+use io_trait::Io;
+use nanvm_lib::mem::global::GLOBAL;
+
+use crate::{
+    app::{
+        add::{dir_to_json, property, Entry},
+        get::{read_dir_block, restore, restore_entry},
+    },
+    cdt::{tree_add::TreeAdd, writer::CdtWriter},
+    common::{base32::ToBase32, hash_format::parse_hash},
+    error::BlocksetError,
+    forest::{file::FileForest, tree_add::ForestTreeAdd},
+    uint::u224::U224,
+};
+
+/// A CDT0 hash. It's displayed as 45 base32 digits and parsed from any `HashFormat`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hash(pub U224);
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_base32())
+    }
+}
+
+impl FromStr for Hash {
+    type Err = io::Error;
+    fn from_str(s: &str) -> io::Result<Self> {
+        parse_hash(s)
+            .map(Hash)
+            .ok_or_else(|| BlocksetError::InvalidHash(s.to_owned()).into())
+    }
+}
+
+impl From<U224> for Hash {
+    fn from(d: U224) -> Self {
+        Hash(d)
+    }
+}
+
+impl From<Hash> for U224 {
+    fn from(h: Hash) -> Self {
+        h.0
+    }
+}
+
+fn read_to_tree(tree_add: impl TreeAdd, mut r: impl Read) -> io::Result<Hash> {
+    let mut w = CdtWriter::new(tree_add);
+    copy(&mut r, &mut w)?;
+    Ok(Hash(w.finish()?.0))
+}
+
+/// Calculates the hash of the content without storing it.
+pub fn hash(r: impl Read) -> io::Result<Hash> {
+    read_to_tree((), r)
+}
+
+/// A handle of the `cdt0` repository in the current directory of `io`.
+pub struct Repository<'a, T: Io> {
+    io: &'a T,
+}
+
+impl<'a, T: Io> Repository<'a, T> {
+    pub fn new(io: &'a T) -> Self {
+        Self { io }
+    }
+    /// Stores the content and returns its hash.
+    pub fn add(&self, r: impl Read) -> io::Result<Hash> {
+        read_to_tree(ForestTreeAdd::new(FileForest(self.io)), r)
+    }
+    /// Writes the content and returns its size.
+    pub fn restore(&self, d: &Hash, w: &mut impl Write) -> io::Result<u64> {
+        restore(self.io, &d.0, w, &mut |_, _| Ok(()))
+    }
+    /// Stores a directory block. Paths use `/` as a separator.
+    pub fn add_directory<'b>(
+        &self,
+        entries: impl IntoIterator<Item = (&'b str, &'b Hash)>,
+    ) -> io::Result<Hash> {
+        let list = entries
+            .into_iter()
+            .map(|(path, d)| property(GLOBAL, path, d.to_string()))
+            .collect::<Vec<_>>();
+        self.add(Cursor::new(dir_to_json(GLOBAL, list.into_iter())?))
+    }
+    /// Returns `(path, entry)` pairs of a directory block.
+    pub fn read_directory(&self, d: &Hash) -> io::Result<Vec<(String, Entry)>> {
+        read_dir_block(self.io, &d.0)
+    }
+    /// Writes the content of a directory entry and returns its size.
+    pub fn restore_entry(&self, entry: &str, w: &mut impl Write) -> io::Result<u64> {
+        restore_entry(self.io, entry, w, &mut |_, _| Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use io_test::VirtualIo;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{hash, Hash, Repository};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let io = VirtualIo::new(&[]);
+        let r = Repository::new(&io);
+        let content = "Hello, world!".repeat(1000);
+        let d = r.add(content.as_bytes()).unwrap();
+        assert_eq!(hash(content.as_bytes()).unwrap(), d);
+        let mut v = Vec::default();
+        assert_eq!(r.restore(&d, &mut v).unwrap(), content.len() as u64);
+        assert_eq!(v, content.as_bytes());
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_hash() {
+        let d = hash(&b"Hello, world!"[..]).unwrap();
+        let s = d.to_string();
+        assert_eq!(s.len(), 45);
+        assert_eq!(s.parse::<Hash>().unwrap(), d);
+        let e = "hello".parse::<Hash>().unwrap_err();
+        assert_eq!(e.to_string(), "invalid hash");
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_directory() {
+        let io = VirtualIo::new(&[]);
+        let r = Repository::new(&io);
+        let a = r.add(&b"a"[..]).unwrap();
+        let b = r.add(&b"b"[..]).unwrap();
+        let d = r.add_directory([("a.txt", &a), ("x/b.txt", &b)]).unwrap();
+        let entries = r.read_directory(&d).unwrap();
+        assert_eq!(
+            entries,
+            [
+                ("a.txt".to_owned(), a.to_string().into()),
+                ("x/b.txt".to_owned(), b.to_string().into())
+            ]
+        );
+        let mut v = Vec::default();
+        r.restore_entry(&entries[1].1.content, &mut v).unwrap();
+        assert_eq!(v, b"b");
+    }
+}
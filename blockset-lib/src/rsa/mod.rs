@@ -0,0 +1,123 @@
+//! RSASSA-PKCS1-v1_5 verification with SHA-256, https://datatracker.ietf.org/doc/html/rfc8017#section-8.2.2
+use crate::uint::mont::{from_be_bytes, less, to_be_bytes, Limbs, Mont};
+
+/// `DigestInfo` of SHA-256 without the digest.
+const SHA256_PREFIX: &[u8] = &[
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// Smaller keys are not accepted.
+const MIN_BITS: usize = 2048;
+
+const MAX_BITS: usize = 16384;
+
+pub struct PublicKey {
+    n: Mont,
+    e: Limbs,
+    /// The size of the modulus in bytes.
+    len: usize,
+}
+
+impl PublicKey {
+    /// The modulus and the exponent are big-endian.
+    pub fn new(n: &[u8], e: &[u8]) -> Option<Self> {
+        let n = &n[n.iter().take_while(|&&b| b == 0).count()..];
+        let bits = (n.len() * 8).checked_sub(n.first()?.leading_zeros() as usize)?;
+        if !(MIN_BITS..=MAX_BITS).contains(&bits) {
+            return None;
+        }
+        let len = n.len();
+        let limbs = len.div_ceil(8);
+        let e = from_be_bytes(e, limbs)?;
+        // an even exponent can't be valid.
+        if e[0] & 1 == 0 || less(&e, &[3]) {
+            return None;
+        }
+        Some(Self {
+            n: Mont::new(from_be_bytes(n, limbs)?)?,
+            e,
+            len,
+        })
+    }
+    pub fn verify(&self, digest: &[u8; 32], signature: &[u8]) -> bool {
+        if signature.len() != self.len {
+            return false;
+        }
+        let Some(s) = from_be_bytes(signature, self.n.len()) else {
+            return false;
+        };
+        if !less(&s, self.n.modulus()) {
+            return false;
+        }
+        let m = self.n.to_normal(&self.n.pow(&self.n.to_mont(&s), &self.e));
+        let Some(padding) = self.len.checked_sub(3 + SHA256_PREFIX.len() + 32) else {
+            return false;
+        };
+        let expected = [
+            &[0, 1],
+            [0xFF].repeat(padding).as_slice(),
+            &[0],
+            SHA256_PREFIX,
+            digest,
+        ]
+        .concat();
+        to_be_bytes(&m, self.len) == expected
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{common::base64::from_base64_str, sha2::sha256::sha256};
+
+    use super::PublicKey;
+
+    /// A 2048-bit key from `openssl genrsa`.
+    const N: &str = concat!(
+        "w1fRy7mPh+hQGY6i80SkejzTgaXDqghQLnwqcGOMVE98h3QkF6AFz3CR6EoD5ywwEH/6+i/NJ9jO",
+        "PZfBmCiH93c/naOh6iAuDOyb89ViujnkUXFGvX0AV6rr1NYwYxQOWWC1zoHQMjMHA/2hWHYGDF4Q",
+        "zFuUHvYMzsdaEtWqIy6YazV9KLnLSdGB0xAEYvMoZ/1QdP2XdltlLYVMj0tPpe7v6GE7I3yrFWSM",
+        "SAraJkw5CgjFBv6dYiadNlrn+tgSO7w2PD5W/mQeOqnF5MGsAx+zUVJFxSMypbplxHy/B22uNyfL",
+        "4xMTaymmZ7zbhI4XsUPKNZnmS8o8LwpRCYFXiQ==",
+    );
+
+    /// `openssl dgst -sha256 -sign` of `MESSAGE`.
+    const SIGNATURE: &str = concat!(
+        "HHvu14qImvRDHVHbuLRliHPoi82mpVK2rLa4O6nYJ7tZRZriIPcHsdDd7h6vMa70eyeB707P83/P",
+        "WVb3HVb6RJhUgL88skud9BgreCZG8loY0J/W+vslWUhRJ5S+p5GoOthiicSBmPh1Cql41rmT7Crb",
+        "+ep3wH6DAQWNE0xF6tYphTCvaJ6KTitwFs/DM5gniJAO7kUmLyAJAK4mgm217RvjXj+TWQEN/cOj",
+        "ZPaA1sRzPo8L3+QJbITebtyInHIZ79CMgackzEY4FrrvoEtTFVDh36KHkt2NkviHIt8g/p6873dc",
+        "19hmp5DspbZvC6VyO+vRVame72SFLSki+rey+w==",
+    );
+
+    const MESSAGE: &[u8] = b"Hello, world!";
+
+    fn key() -> PublicKey {
+        PublicKey::new(&from_base64_str(N).unwrap(), &[1, 0, 1]).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        let k = key();
+        let mut s = from_base64_str(SIGNATURE).unwrap();
+        assert!(k.verify(&sha256(MESSAGE), &s));
+        assert!(!k.verify(&sha256(b"Hello, world?"), &s));
+        s[100] ^= 1;
+        assert!(!k.verify(&sha256(MESSAGE), &s));
+        assert!(!k.verify(&sha256(MESSAGE), &s[1..]));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_invalid_key() {
+        let n = from_base64_str(N).unwrap();
+        // even exponent
+        assert!(PublicKey::new(&n, &[1, 0, 0]).is_none());
+        // too short
+        assert!(PublicKey::new(&n[1..], &[1, 0, 1]).is_none());
+        assert!(PublicKey::new(&[], &[1, 0, 1]).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+//! Streaming SHA-256 and SHA-224, https://datatracker.ietf.org/doc/html/rfc6234
+use std::io::{self, Write};
+
+use crate::uint::{
+    u128::{from_u32x4, get_u32},
+    u256::U256,
+    u512::new,
+};
+
+use super::{compress::compress, sha224::SHA224, sha256::SHA256};
+
+pub struct Hasher {
+    state: U256,
+    block: [u8; 64],
+    /// The number of hashed bytes.
+    len: u64,
+    /// The digest size in bytes.
+    size: usize,
+}
+
+const fn word(b: &[u8; 64], i: usize) -> u32 {
+    u32::from_be_bytes([b[i * 4], b[i * 4 + 1], b[i * 4 + 2], b[i * 4 + 3]])
+}
+
+const fn words(b: &[u8; 64], i: usize) -> u128 {
+    let i = i * 4;
+    from_u32x4([word(b, i), word(b, i + 1), word(b, i + 2), word(b, i + 3)])
+}
+
+impl Hasher {
+    const fn new(state: U256, size: usize) -> Self {
+        Self {
+            state,
+            block: [0; 64],
+            len: 0,
+            size,
+        }
+    }
+    pub const fn sha256() -> Self {
+        Self::new(SHA256, 32)
+    }
+    pub const fn sha224() -> Self {
+        Self::new(SHA224, 28)
+    }
+    fn compress(&mut self) {
+        let b = &self.block;
+        let w = new(words(b, 0), words(b, 1), words(b, 2), words(b, 3));
+        self.state = compress(self.state, w);
+    }
+    pub fn update(&mut self, v: &[u8]) {
+        for &c in v {
+            let i = (self.len % 64) as usize;
+            self.block[i] = c;
+            self.len += 1;
+            if i == 63 {
+                self.compress();
+            }
+        }
+    }
+    pub fn finish(mut self) -> Vec<u8> {
+        let bits = self.len << 3;
+        self.update(&[0x80]);
+        while self.len % 64 != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+        (0..self.size / 4)
+            .flat_map(|i| get_u32(self.state[i / 4], i % 4).to_be_bytes())
+            .collect()
+    }
+}
+
+impl Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::Hasher;
+
+    fn hex(mut h: Hasher, v: &[u8]) -> String {
+        h.update(v);
+        h.finish().iter().map(|x| format!("{:02x}", x)).collect()
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        assert_eq!(
+            hex(Hasher::sha256(), b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(Hasher::sha256(), b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(Hasher::sha224(), b""),
+            "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"
+        );
+        assert_eq!(
+            hex(Hasher::sha224(), b"abc"),
+            "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_long() {
+        // 55 and 56 bytes are the edge cases of padding.
+        for (n, h256, h224) in [
+            (
+                55,
+                "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318",
+                "fb0bd626a70c28541dfa781bb5cc4d7d7f56622a58f01a0b1ddd646f",
+            ),
+            (
+                56,
+                "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a",
+                "d40854fc9caf172067136f2e29e1380b14626bf6f0dd06779f820dcd",
+            ),
+            (
+                64,
+                "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb",
+                "a88cd5cde6d6fe9136a4e58b49167461ea95d388ca2bdb7afdc3cbf4",
+            ),
+            (
+                1000,
+                "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3",
+                "4e8f0ce90b64661a2b5e84be6d93a7d9b76871062f1814433d04a03d",
+            ),
+        ] {
+            let v = b"a".repeat(n);
+            assert_eq!(hex(Hasher::sha256(), &v), h256);
+            assert_eq!(hex(Hasher::sha224(), &v), h224);
+        }
+    }
+}
@@ -4,4 +4,7 @@ mod w_round;
 
 // pub
 pub mod compress;
+pub mod hasher;
 pub mod sha224;
+pub mod sha256;
+pub mod sha512;
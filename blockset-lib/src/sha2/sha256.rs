@@ -0,0 +1,16 @@
+use crate::uint::u256::U256;
+
+use super::hasher::Hasher;
+
+pub const SHA256: U256 = [
+    0xa54ff53a_3c6ef372_bb67ae85_6a09e667,
+    0x5be0cd19_1f83d9ab_9b05688c_510e527f,
+];
+
+pub fn sha256(v: &[u8]) -> [u8; 32] {
+    let mut h = Hasher::sha256();
+    h.update(v);
+    let mut result = [0; 32];
+    result.copy_from_slice(&h.finish());
+    result
+}
@@ -0,0 +1,231 @@
+pub type U64x8 = [u64; 8];
+
+pub type U64x16 = [u64; 16];
+
+const K: [u64; 80] = [
+    0x428a2f98_d728ae22,
+    0x71374491_23ef65cd,
+    0xb5c0fbcf_ec4d3b2f,
+    0xe9b5dba5_8189dbbc,
+    0x3956c25b_f348b538,
+    0x59f111f1_b605d019,
+    0x923f82a4_af194f9b,
+    0xab1c5ed5_da6d8118,
+    0xd807aa98_a3030242,
+    0x12835b01_45706fbe,
+    0x243185be_4ee4b28c,
+    0x550c7dc3_d5ffb4e2,
+    0x72be5d74_f27b896f,
+    0x80deb1fe_3b1696b1,
+    0x9bdc06a7_25c71235,
+    0xc19bf174_cf692694,
+    0xe49b69c1_9ef14ad2,
+    0xefbe4786_384f25e3,
+    0x0fc19dc6_8b8cd5b5,
+    0x240ca1cc_77ac9c65,
+    0x2de92c6f_592b0275,
+    0x4a7484aa_6ea6e483,
+    0x5cb0a9dc_bd41fbd4,
+    0x76f988da_831153b5,
+    0x983e5152_ee66dfab,
+    0xa831c66d_2db43210,
+    0xb00327c8_98fb213f,
+    0xbf597fc7_beef0ee4,
+    0xc6e00bf3_3da88fc2,
+    0xd5a79147_930aa725,
+    0x06ca6351_e003826f,
+    0x14292967_0a0e6e70,
+    0x27b70a85_46d22ffc,
+    0x2e1b2138_5c26c926,
+    0x4d2c6dfc_5ac42aed,
+    0x53380d13_9d95b3df,
+    0x650a7354_8baf63de,
+    0x766a0abb_3c77b2a8,
+    0x81c2c92e_47edaee6,
+    0x92722c85_1482353b,
+    0xa2bfe8a1_4cf10364,
+    0xa81a664b_bc423001,
+    0xc24b8b70_d0f89791,
+    0xc76c51a3_0654be30,
+    0xd192e819_d6ef5218,
+    0xd6990624_5565a910,
+    0xf40e3585_5771202a,
+    0x106aa070_32bbd1b8,
+    0x19a4c116_b8d2d0c8,
+    0x1e376c08_5141ab53,
+    0x2748774c_df8eeb99,
+    0x34b0bcb5_e19b48a8,
+    0x391c0cb3_c5c95a63,
+    0x4ed8aa4a_e3418acb,
+    0x5b9cca4f_7763e373,
+    0x682e6ff3_d6b2b8a3,
+    0x748f82ee_5defb2fc,
+    0x78a5636f_43172f60,
+    0x84c87814_a1f0ab72,
+    0x8cc70208_1a6439ec,
+    0x90befffa_23631e28,
+    0xa4506ceb_de82bde9,
+    0xbef9a3f7_b2c67915,
+    0xc67178f2_e372532b,
+    0xca273ece_ea26619c,
+    0xd186b8c7_21c0c207,
+    0xeada7dd6_cde0eb1e,
+    0xf57d4f7f_ee6ed178,
+    0x06f067aa_72176fba,
+    0x0a637dc5_a2c898a6,
+    0x113f9804_bef90dae,
+    0x1b710b35_131c471b,
+    0x28db77f5_23047d84,
+    0x32caab7b_40c72493,
+    0x3c9ebe0a_15c9bebc,
+    0x431d67c4_9c100d4c,
+    0x4cc5d4be_cb3e42b6,
+    0x597f299c_fc657e2a,
+    0x5fcb6fab_3ad6faec,
+    0x6c44198c_4a475817,
+];
+
+pub const SHA512: U64x8 = [
+    0x6a09e667_f3bcc908,
+    0xbb67ae85_84caa73b,
+    0x3c6ef372_fe94f82b,
+    0xa54ff53a_5f1d36f1,
+    0x510e527f_ade682d1,
+    0x9b05688c_2b3e6c1f,
+    0x1f83d9ab_fb41bd6b,
+    0x5be0cd19_137e2179,
+];
+
+#[inline(always)]
+const fn big0(x: u64) -> u64 {
+    x.rotate_right(28) ^ x.rotate_right(34) ^ x.rotate_right(39)
+}
+
+#[inline(always)]
+const fn big1(x: u64) -> u64 {
+    x.rotate_right(14) ^ x.rotate_right(18) ^ x.rotate_right(41)
+}
+
+#[inline(always)]
+const fn small0(x: u64) -> u64 {
+    x.rotate_right(1) ^ x.rotate_right(8) ^ (x >> 7)
+}
+
+#[inline(always)]
+const fn small1(x: u64) -> u64 {
+    x.rotate_right(19) ^ x.rotate_right(61) ^ (x >> 6)
+}
+
+pub const fn compress512(init: U64x8, w: U64x16) -> U64x8 {
+    let mut ws = [0; 80];
+    let mut i = 0;
+    while i < 16 {
+        ws[i] = w[i];
+        i += 1;
+    }
+    while i < 80 {
+        ws[i] = small1(ws[i - 2])
+            .wrapping_add(ws[i - 7])
+            .wrapping_add(small0(ws[i - 15]))
+            .wrapping_add(ws[i - 16]);
+        i += 1;
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = init;
+    i = 0;
+    while i < 80 {
+        let t1 = h
+            .wrapping_add(big1(e))
+            .wrapping_add((e & f) ^ (!e & g))
+            .wrapping_add(K[i])
+            .wrapping_add(ws[i]);
+        let t2 = big0(a).wrapping_add((a & b) ^ (a & c) ^ (b & c));
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        i += 1;
+    }
+    let x = [a, b, c, d, e, f, g, h];
+    let mut result = init;
+    i = 0;
+    while i < 8 {
+        result[i] = result[i].wrapping_add(x[i]);
+        i += 1;
+    }
+    result
+}
+
+fn block(chunk: &[u8]) -> U64x16 {
+    let mut w = U64x16::default();
+    for (wi, c) in w.iter_mut().zip(chunk.chunks(8)) {
+        *wi = u64::from_be_bytes(c.try_into().unwrap());
+    }
+    w
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc6234
+pub fn sha512(v: &[u8]) -> [u8; 64] {
+    let mut padded = v.to_vec();
+    padded.push(0x80);
+    while padded.len() % 128 != 112 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((v.len() as u128) << 3).to_be_bytes());
+    let h = padded
+        .chunks(128)
+        .fold(SHA512, |h, chunk| compress512(h, block(chunk)));
+    let mut result = [0; 64];
+    for (r, x) in result.chunks_mut(8).zip(h) {
+        r.copy_from_slice(&x.to_be_bytes());
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::sha512;
+
+    fn hex(v: &[u8]) -> String {
+        v.iter().map(|x| format!("{:02x}", x)).collect()
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test() {
+        assert_eq!(
+            hex(&sha512(b"")),
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        );
+        assert_eq!(
+            hex(&sha512(b"abc")),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            hex(&sha512(
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"
+            )),
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+             501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_long() {
+        // 111 and 112 bytes are the edge cases of padding.
+        for (n, h) in [
+            (111, "fa9121c7b32b9e01733d034cfc78cbf67f926c7ed83e82200ef86818196921760b4beff48404df811b953828274461673c68d04e297b0eb7b2b4d60fc6b566a2"),
+            (112, "c01d080efd492776a1c43bd23dd99d0a2e626d481e16782e75d54c2503b5dc32bd05f0f1ba33e568b88fd2d970929b719ecbb152f58f130a407c8830604b70ca"),
+        ] {
+            assert_eq!(hex(&sha512(&b"a".repeat(n))), h);
+        }
+    }
+}
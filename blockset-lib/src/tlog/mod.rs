@@ -0,0 +1,265 @@
+//! A Merkle tree log, https://datatracker.ietf.org/doc/html/rfc9162#section-2.1
+use crate::{
+    sha2::{compress::compress, sha224::SHA224},
+    uint::{
+        u128::{from_u32x4, to_u32x4},
+        u224::U224,
+        u256::U256,
+    },
+};
+
+// domain separation tags in the last 32-bit word.
+const EMPTY: u32 = 0;
+const LEAF: u32 = 1;
+const NODE: u32 = 2;
+
+const fn tagged(&[a0, a1, a2, a3, a4, a5, a6]: &U224, tag: u32) -> U256 {
+    [from_u32x4([a0, a1, a2, a3]), from_u32x4([a4, a5, a6, tag])]
+}
+
+const fn hash(a: U256, b: U256) -> U224 {
+    let [x0, x1] = compress(SHA224, [a, b]);
+    let [a0, a1, a2, a3] = to_u32x4(x0);
+    let [a4, a5, a6, _] = to_u32x4(x1);
+    [a0, a1, a2, a3, a4, a5, a6]
+}
+
+pub const fn empty_hash() -> U224 {
+    hash(tagged(&[0; 7], EMPTY), [0, 0])
+}
+
+pub const fn leaf_hash(d: &U224) -> U224 {
+    hash(tagged(d, LEAF), [0, 0])
+}
+
+pub const fn node_hash(a: &U224, b: &U224) -> U224 {
+    hash(tagged(a, NODE), tagged(b, NODE))
+}
+
+/// The largest power of two smaller than `n`.
+pub const fn split(n: usize) -> usize {
+    1 << (usize::BITS - (n - 1).leading_zeros() - 1)
+}
+
+fn path<E>(
+    lo: usize,
+    hi: usize,
+    m: usize,
+    mth: &mut impl FnMut(usize, usize) -> Result<U224, E>,
+) -> Result<Vec<U224>, E> {
+    if hi - lo <= 1 {
+        return Ok(Vec::default());
+    }
+    let k = lo + split(hi - lo);
+    let (mut result, h) = if m < k {
+        (path(lo, k, m, mth)?, mth(k, hi)?)
+    } else {
+        (path(k, hi, m, mth)?, mth(lo, k)?)
+    };
+    result.push(h);
+    Ok(result)
+}
+
+/// `PATH(m, D[n])`. `mth(a, b)` returns `MTH(D[a:b])`, it's called for perfect subtrees and
+/// suffixes of `D[n]` only.
+pub fn inclusion_proof<E>(
+    n: usize,
+    m: usize,
+    mth: &mut impl FnMut(usize, usize) -> Result<U224, E>,
+) -> Result<Vec<U224>, E> {
+    path(0, n, m, mth)
+}
+
+fn sub_proof<E>(
+    lo: usize,
+    hi: usize,
+    m: usize,
+    b: bool,
+    mth: &mut impl FnMut(usize, usize) -> Result<U224, E>,
+) -> Result<Vec<U224>, E> {
+    if m == hi {
+        return Ok(if b {
+            Vec::default()
+        } else {
+            [mth(lo, hi)?].to_vec()
+        });
+    }
+    let k = lo + split(hi - lo);
+    let (mut result, h) = if m <= k {
+        (sub_proof(lo, k, m, b, mth)?, mth(k, hi)?)
+    } else {
+        (sub_proof(k, hi, m, false, mth)?, mth(lo, k)?)
+    };
+    result.push(h);
+    Ok(result)
+}
+
+/// `PROOF(m, D[n])`, `mth` is the same as for `inclusion_proof`.
+pub fn consistency_proof<E>(
+    n: usize,
+    m: usize,
+    mth: &mut impl FnMut(usize, usize) -> Result<U224, E>,
+) -> Result<Vec<U224>, E> {
+    if m == 0 || m >= n {
+        return Ok(Vec::default());
+    }
+    sub_proof(0, n, m, true, mth)
+}
+
+const fn shift_while_lsb(mut f: usize, mut s: usize, lsb: bool) -> (usize, usize) {
+    while (f & 1 == 1) == lsb && f != 0 {
+        f >>= 1;
+        s >>= 1;
+    }
+    (f, s)
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc9162#section-2.1.3.2
+pub fn verify_inclusion(
+    leaf: &U224,
+    index: usize,
+    size: usize,
+    path: &[U224],
+    root: &U224,
+) -> bool {
+    if index >= size {
+        return false;
+    }
+    let (mut f, mut s) = (index, size - 1);
+    let mut r = *leaf;
+    for p in path {
+        if s == 0 {
+            return false;
+        }
+        if f & 1 == 1 || f == s {
+            r = node_hash(p, &r);
+            (f, s) = shift_while_lsb(f, s, false);
+        } else {
+            r = node_hash(&r, p);
+        }
+        f >>= 1;
+        s >>= 1;
+    }
+    s == 0 && r == *root
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc9162#section-2.1.4.2
+pub fn verify_consistency(
+    first: usize,
+    second: usize,
+    first_root: &U224,
+    second_root: &U224,
+    path: &[U224],
+) -> bool {
+    if first == second {
+        return path.is_empty() && first_root == second_root;
+    }
+    if first == 0 || first > second || path.is_empty() {
+        return false;
+    }
+    let path = if first.is_power_of_two() {
+        [[*first_root].as_slice(), path].concat()
+    } else {
+        path.to_vec()
+    };
+    let (mut f, mut s) = shift_while_lsb(first - 1, second - 1, true);
+    let (mut fr, mut sr) = (path[0], path[0]);
+    for c in &path[1..] {
+        if s == 0 {
+            return false;
+        }
+        if f & 1 == 1 || f == s {
+            fr = node_hash(c, &fr);
+            sr = node_hash(c, &sr);
+            (f, s) = shift_while_lsb(f, s, false);
+        } else {
+            sr = node_hash(&sr, c);
+        }
+        f >>= 1;
+        s >>= 1;
+    }
+    fr == *first_root && sr == *second_root && s == 0
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::uint::u224::U224;
+
+    use super::{
+        consistency_proof, empty_hash, inclusion_proof, leaf_hash, node_hash, split,
+        verify_consistency, verify_inclusion,
+    };
+
+    /// `MTH(D[n])`, `leaves` are leaf hashes.
+    fn tree_hash(leaves: &[U224]) -> U224 {
+        match leaves.len() {
+            0 => empty_hash(),
+            1 => leaves[0],
+            n => {
+                let k = split(n);
+                node_hash(&tree_hash(&leaves[..k]), &tree_hash(&leaves[k..]))
+            }
+        }
+    }
+
+    fn leaves(n: u32) -> Vec<U224> {
+        (0..n).map(|i| leaf_hash(&[i, 0, 0, 0, 0, 0, 0])).collect()
+    }
+
+    fn mth(l: &[U224]) -> impl FnMut(usize, usize) -> Result<U224, ()> + '_ {
+        |a, b| Ok(tree_hash(&l[a..b]))
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_tree_hash() {
+        let l = leaves(3);
+        assert_eq!(tree_hash(&[]), empty_hash());
+        assert_eq!(tree_hash(&l[..1]), l[0]);
+        assert_eq!(tree_hash(&l), node_hash(&node_hash(&l[0], &l[1]), &l[2]));
+        assert_ne!(leaf_hash(&[0; 7]), empty_hash());
+        assert_ne!(node_hash(&l[0], &l[1]), node_hash(&l[1], &l[0]));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_inclusion() {
+        for n in 1..20 {
+            let l = leaves(n);
+            let root = tree_hash(&l);
+            for m in 0..n as usize {
+                let p = inclusion_proof(l.len(), m, &mut mth(&l)).unwrap();
+                assert!(verify_inclusion(&l[m], m, n as usize, &p, &root));
+                assert!(!verify_inclusion(&l[m], m + 1, n as usize, &p, &root));
+                if n > 1 {
+                    assert!(!verify_inclusion(&root, m, n as usize, &p, &root));
+                }
+                if let Some((_, rest)) = p.split_last() {
+                    assert!(!verify_inclusion(&l[m], m, n as usize, rest, &root));
+                }
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_consistency() {
+        let l = leaves(20);
+        for n in 1..20 {
+            let second = tree_hash(&l[..n]);
+            for m in 1..=n {
+                let first = tree_hash(&l[..m]);
+                let p = consistency_proof(n, m, &mut mth(&l)).unwrap();
+                assert!(verify_consistency(m, n, &first, &second, &p));
+                if m < n {
+                    assert!(!verify_consistency(m, n, &second, &second, &p));
+                    let mut q = p.clone();
+                    q[0] = l[n];
+                    assert!(!verify_consistency(m, n, &first, &second, &q));
+                }
+            }
+        }
+    }
+}
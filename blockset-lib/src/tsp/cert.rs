@@ -0,0 +1,149 @@
+use crate::asn1::der::{
+    context, decode, Tlv, BIT_STRING, BOOLEAN, GENERALIZED_TIME, OCTET_STRING, SEQUENCE, UTC_TIME,
+};
+
+use super::{children, get, is_oid, key::PublicKey, Error};
+
+const INVALID: Error = "invalid certificate";
+
+const EXTENDED_KEY_USAGE: &[u64] = &[2, 5, 29, 37];
+
+const TIME_STAMPING: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 8];
+
+const BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+
+const KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+
+/// The `keyCertSign` bit in the first byte of `KeyUsage`.
+const KEY_CERT_SIGN: u8 = 0x04;
+
+/// https://datatracker.ietf.org/doc/html/rfc5280#section-4.1
+pub struct Certificate<'a> {
+    tbs: Tlv<'a>,
+    signature_algorithm: Tlv<'a>,
+    signature: &'a [u8],
+    pub serial: Tlv<'a>,
+    pub issuer: Tlv<'a>,
+    pub subject: Tlv<'a>,
+    pub not_before: String,
+    pub not_after: String,
+    pub public_key: PublicKey,
+    pub time_stamping: bool,
+    /// `cA` of `BasicConstraints`.
+    pub is_ca: bool,
+    /// `true` if `KeyUsage` is absent.
+    pub key_cert_sign: bool,
+}
+
+/// Converts `UTCTime` and `GeneralizedTime` to `YYYYMMDDHHMMSSZ`.
+pub fn normalize_time(t: Tlv) -> Result<String, Error> {
+    let s = core::str::from_utf8(t.content).map_err(|_| INVALID)?;
+    let s = match t.tag {
+        UTC_TIME if s.len() == 13 => (if &s[..2] < "50" { "20" } else { "19" }).to_owned() + s,
+        GENERALIZED_TIME if s.len() >= 15 => s.to_owned(),
+        _ => return Err(INVALID),
+    };
+    if !s.ends_with('Z') || !s.as_bytes()[..14].iter().all(u8::is_ascii_digit) {
+        return Err(INVALID);
+    }
+    Ok(s[..14].to_owned() + "Z")
+}
+
+fn bit_string<'a>(t: Tlv<'a>) -> Result<&'a [u8], Error> {
+    match t.expect(BIT_STRING).ok_or(INVALID)?.content.split_first() {
+        Some((0, v)) => Ok(v),
+        _ => Err(INVALID),
+    }
+}
+
+struct Extensions {
+    time_stamping: bool,
+    is_ca: bool,
+    key_cert_sign: bool,
+}
+
+fn parse_extensions(extensions: Option<Tlv>) -> Result<Extensions, Error> {
+    let mut result = Extensions {
+        time_stamping: false,
+        is_ca: false,
+        key_cert_sign: true,
+    };
+    let Some(extensions) = extensions else {
+        return Ok(result);
+    };
+    for e in children(get(&children(extensions, context(3))?, 0)?, SEQUENCE)? {
+        let e = children(e, SEQUENCE)?;
+        let value = || {
+            let value = get(&e, if get(&e, 1)?.tag == BOOLEAN { 2 } else { 1 })?;
+            let (value, _) =
+                decode(value.expect(OCTET_STRING).ok_or(INVALID)?.content).ok_or(INVALID)?;
+            Ok(value)
+        };
+        let oid = get(&e, 0)?;
+        let critical = get(&e, 1)?;
+        let critical = critical.tag == BOOLEAN && critical.content != [0];
+        if is_oid(oid, EXTENDED_KEY_USAGE) {
+            result.time_stamping = children(value()?, SEQUENCE)?
+                .into_iter()
+                .any(|u| is_oid(u, TIME_STAMPING));
+        } else if is_oid(oid, BASIC_CONSTRAINTS) {
+            // `cA` is `FALSE` by default.
+            result.is_ca = children(value()?, SEQUENCE)?
+                .first()
+                .is_some_and(|c| c.tag == BOOLEAN && c.content != [0]);
+        } else if is_oid(oid, KEY_USAGE) {
+            // the first byte is the number of unused bits.
+            let bits = value()?.expect(BIT_STRING).ok_or(INVALID)?.content;
+            result.key_cert_sign = bits.get(1).is_some_and(|b| b & KEY_CERT_SIGN != 0);
+        } else if critical {
+            // https://datatracker.ietf.org/doc/html/rfc5280#section-4.2
+            return Err("unsupported critical extension");
+        }
+    }
+    Ok(result)
+}
+
+impl<'a> Certificate<'a> {
+    /// Supports Ed25519, RSA and ECDSA P-256 keys.
+    pub fn parse(v: &'a [u8]) -> Result<Self, Error> {
+        let (c, rest) = decode(v).ok_or(INVALID)?;
+        if !rest.is_empty() {
+            return Err(INVALID);
+        }
+        let c = children(c, SEQUENCE)?;
+        let tbs = get(&c, 0)?;
+        let t = children(tbs, SEQUENCE)?;
+        // the version is optional.
+        let i = usize::from(get(&t, 0)?.tag == context(0));
+        let validity = children(get(&t, i + 3)?, SEQUENCE)?;
+        let spki = children(get(&t, i + 5)?, SEQUENCE)?;
+        let extensions = parse_extensions(t.last().copied().filter(|e| e.tag == context(3)))?;
+        Ok(Self {
+            tbs,
+            signature_algorithm: get(&c, 1)?,
+            signature: bit_string(get(&c, 2)?)?,
+            serial: get(&t, i)?,
+            issuer: get(&t, i + 2)?,
+            subject: get(&t, i + 4)?,
+            not_before: normalize_time(get(&validity, 0)?)?,
+            not_after: normalize_time(get(&validity, 1)?)?,
+            public_key: PublicKey::parse(get(&spki, 0)?, bit_string(get(&spki, 1)?)?)?,
+            time_stamping: extensions.time_stamping,
+            is_ca: extensions.is_ca,
+            key_cert_sign: extensions.key_cert_sign,
+        })
+    }
+    /// The issuer should be a CA which may sign certificates.
+    pub fn is_issued_by(&self, ca: &Certificate) -> bool {
+        self.issuer.raw == ca.subject.raw
+            && ca.is_ca
+            && ca.key_cert_sign
+            && ca
+                .public_key
+                .verify(self.signature_algorithm, self.tbs.raw, self.signature)
+                == Ok(true)
+    }
+    pub fn is_valid_at(&self, time: &str) -> bool {
+        self.not_before.as_str() <= time && time <= self.not_after.as_str()
+    }
+}
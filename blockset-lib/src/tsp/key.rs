@@ -0,0 +1,81 @@
+//! Public keys of certificates and signature algorithms, https://datatracker.ietf.org/doc/html/rfc5480
+use crate::{
+    asn1::der::{decode, Tlv, INTEGER, SEQUENCE},
+    ed25519, p256, rsa,
+    sha2::sha256::sha256,
+};
+
+use super::{check_algorithm, children, get, is_oid, Error, ED25519};
+
+const INVALID: Error = "invalid public key";
+
+const RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113549, 1, 1, 1];
+
+const SHA256_WITH_RSA: &[u64] = &[1, 2, 840, 113549, 1, 1, 11];
+
+const EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+
+const PRIME256V1: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+
+const ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+
+pub enum PublicKey {
+    Ed25519(ed25519::PublicKey),
+    Rsa(rsa::PublicKey),
+    P256(p256::PublicKey),
+}
+
+fn unsupported<T>() -> Result<T, Error> {
+    Err("unsupported algorithm")
+}
+
+/// `SEQUENCE { INTEGER, INTEGER }`, for an RSA public key and an ECDSA signature.
+fn integer_pair(v: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (t, rest) = decode(v)?;
+    match t.expect(SEQUENCE)?.children()?.as_slice() {
+        [a, b] if rest.is_empty() => Some((a.expect(INTEGER)?.content, b.expect(INTEGER)?.content)),
+        _ => None,
+    }
+}
+
+impl PublicKey {
+    /// Parses `SubjectPublicKeyInfo` of Ed25519, RSA or ECDSA P-256.
+    pub fn parse(algorithm: Tlv, key: &[u8]) -> Result<Self, Error> {
+        let a = children(algorithm, SEQUENCE)?;
+        let oid = get(&a, 0)?;
+        if is_oid(oid, EC_PUBLIC_KEY) {
+            if !is_oid(get(&a, 1)?, PRIME256V1) {
+                return unsupported();
+            }
+            return p256::PublicKey::parse(key).map(Self::P256).ok_or(INVALID);
+        }
+        if check_algorithm(algorithm, RSA_ENCRYPTION).is_ok() {
+            let (n, e) = integer_pair(key).ok_or(INVALID)?;
+            return rsa::PublicKey::new(n, e).map(Self::Rsa).ok_or(INVALID);
+        }
+        check_algorithm(algorithm, ED25519)?;
+        Ok(Self::Ed25519(key.try_into().map_err(|_| INVALID)?))
+    }
+    /// Verifies a signature of the message. The algorithm should match the key, RSA and ECDSA
+    /// signatures are over SHA-256.
+    pub fn verify(&self, algorithm: Tlv, message: &[u8], signature: &[u8]) -> Result<bool, Error> {
+        Ok(match self {
+            Self::Ed25519(k) => {
+                check_algorithm(algorithm, ED25519)?;
+                signature
+                    .try_into()
+                    .is_ok_and(|s| ed25519::verify(k, message, s))
+            }
+            Self::Rsa(k) => {
+                // a `SignerInfo` may name the key algorithm instead of the signature one.
+                check_algorithm(algorithm, SHA256_WITH_RSA)
+                    .or_else(|_| check_algorithm(algorithm, RSA_ENCRYPTION))?;
+                k.verify(&sha256(message), signature)
+            }
+            Self::P256(k) => {
+                check_algorithm(algorithm, ECDSA_WITH_SHA256)?;
+                integer_pair(signature).is_some_and(|(r, s)| k.verify(&sha256(message), r, s))
+            }
+        })
+    }
+}
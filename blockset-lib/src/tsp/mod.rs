@@ -0,0 +1,456 @@
+//! https://datatracker.ietf.org/doc/html/rfc3161
+mod cert;
+mod key;
+
+use crate::{
+    asn1::der::{
+        context, decode, encode, encode_all, encode_boolean, encode_integer, encode_oid, Tlv, NULL,
+        OCTET_STRING, SEQUENCE, SET,
+    },
+    common::base32::ToBase32,
+    sha2::{sha256::sha256, sha512::sha512},
+    uint::u224::U224,
+};
+
+use self::cert::{normalize_time, Certificate};
+
+pub type Error = &'static str;
+
+const INVALID: Error = "invalid time stamp token";
+
+const SHA256: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 1];
+
+const SHA512: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 3];
+
+const ED25519: &[u64] = &[1, 3, 101, 112];
+
+const SIGNED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 2];
+
+const CONTENT_TYPE: &[u64] = &[1, 2, 840, 113549, 1, 9, 3];
+
+const MESSAGE_DIGEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 4];
+
+const TST_INFO: &[u64] = &[1, 2, 840, 113549, 1, 9, 16, 1, 4];
+
+fn children<'a>(t: Tlv<'a>, tag: u8) -> Result<Vec<Tlv<'a>>, Error> {
+    t.expect(tag).and_then(|t| t.children()).ok_or(INVALID)
+}
+
+fn get<'a>(v: &[Tlv<'a>], i: usize) -> Result<Tlv<'a>, Error> {
+    v.get(i).copied().ok_or(INVALID)
+}
+
+fn is_oid(t: Tlv, oid: &[u64]) -> bool {
+    t.oid().as_deref() == Some(oid)
+}
+
+fn octet_string<'a>(t: Tlv<'a>) -> Result<&'a [u8], Error> {
+    Ok(t.expect(OCTET_STRING).ok_or(INVALID)?.content)
+}
+
+/// Parameters of the algorithm should be absent or `NULL`.
+fn check_algorithm(t: Tlv, oid: &[u64]) -> Result<(), Error> {
+    match children(t, SEQUENCE)?.as_slice() {
+        [a] | [a, Tlv { tag: NULL, .. }] if is_oid(*a, oid) => Ok(()),
+        _ => Err("unsupported algorithm"),
+    }
+}
+
+/// The message imprint of a data address is SHA-512 of its base32 form.
+pub fn message_imprint(address: &U224) -> [u8; 64] {
+    sha512(address.to_base32().as_bytes())
+}
+
+/// Creates a DER encoded `TimeStampReq` which asks for the TSA certificate.
+pub fn request(address: &U224) -> Result<Vec<u8>, Error> {
+    let sha512 = encode_oid(SHA512).ok_or("invalid object identifier")?;
+    Ok(encode_all(
+        SEQUENCE,
+        &[
+            encode_integer(1),
+            encode_all(
+                SEQUENCE,
+                &[
+                    encode_all(SEQUENCE, &[sha512, encode(NULL, &[])]),
+                    encode(OCTET_STRING, &message_imprint(address)),
+                ],
+            ),
+            encode_boolean(true),
+        ],
+    ))
+}
+
+struct SignedData<'a> {
+    tst_info: &'a [u8],
+    certificates: Vec<Tlv<'a>>,
+    signer_info: Vec<Tlv<'a>>,
+}
+
+/// https://datatracker.ietf.org/doc/html/rfc5652#section-5.1
+fn signed_data(token: &[u8]) -> Result<SignedData<'_>, Error> {
+    let (ci, rest) = decode(token).ok_or(INVALID)?;
+    let ci = children(ci, SEQUENCE)?;
+    if !rest.is_empty() || !is_oid(get(&ci, 0)?, SIGNED_DATA) {
+        return Err(INVALID);
+    }
+    let sd = children(get(&children(get(&ci, 1)?, context(0))?, 0)?, SEQUENCE)?;
+    let eci = children(get(&sd, 2)?, SEQUENCE)?;
+    if !is_oid(get(&eci, 0)?, TST_INFO) {
+        return Err(INVALID);
+    }
+    let tst_info = octet_string(get(&children(get(&eci, 1)?, context(0))?, 0)?)?;
+    let mut i = 3;
+    let certificates = if get(&sd, i)?.tag == context(0) {
+        i += 1;
+        children(sd[3], context(0))?
+    } else {
+        Vec::default()
+    };
+    // CRLs are ignored.
+    if get(&sd, i)?.tag == context(1) {
+        i += 1;
+    }
+    let signer_infos = children(get(&sd, i)?, SET)?;
+    Ok(SignedData {
+        tst_info,
+        certificates,
+        signer_info: children(get(&signer_infos, 0)?, SEQUENCE)?,
+    })
+}
+
+pub struct TstInfo {
+    pub hashed_message: Vec<u8>,
+    pub gen_time: String,
+}
+
+fn parse_tst_info(v: &[u8]) -> Result<TstInfo, Error> {
+    let (t, rest) = decode(v).ok_or(INVALID)?;
+    let t = children(t, SEQUENCE)?;
+    let imprint = children(get(&t, 2)?, SEQUENCE)?;
+    check_algorithm(get(&imprint, 0)?, SHA512)?;
+    if !rest.is_empty() {
+        return Err(INVALID);
+    }
+    Ok(TstInfo {
+        hashed_message: octet_string(get(&imprint, 1)?)?.to_vec(),
+        gen_time: normalize_time(get(&t, 4)?)?,
+    })
+}
+
+/// Reads `TSTInfo` of a token without verification.
+pub fn tst_info(token: &[u8]) -> Result<TstInfo, Error> {
+    parse_tst_info(signed_data(token)?.tst_info)
+}
+
+/// SHA-256 or SHA-512, the digest algorithm of a `SignerInfo`.
+fn digest(algorithm: Tlv, v: &[u8]) -> Result<Vec<u8>, Error> {
+    if check_algorithm(algorithm, SHA256).is_ok() {
+        return Ok(sha256(v).to_vec());
+    }
+    check_algorithm(algorithm, SHA512)?;
+    Ok(sha512(v).to_vec())
+}
+
+fn check_signed_attributes(attributes: Tlv, digest: &[u8]) -> Result<(), Error> {
+    let mut content_type = false;
+    let mut message_digest = false;
+    for a in children(attributes, context(0))? {
+        let a = children(a, SEQUENCE)?;
+        let value = get(&children(get(&a, 1)?, SET)?, 0)?;
+        let oid = get(&a, 0)?;
+        if is_oid(oid, CONTENT_TYPE) {
+            content_type = is_oid(value, TST_INFO);
+        } else if is_oid(oid, MESSAGE_DIGEST) {
+            message_digest = octet_string(value)? == digest;
+        }
+    }
+    if !content_type || !message_digest {
+        return Err("message digest mismatch");
+    }
+    Ok(())
+}
+
+/// Verifies a `TimeStampToken` of the data address against the CA certificate and returns
+/// the time in `YYYYMMDDHHMMSSZ` format. Ed25519, RSA and ECDSA P-256 signatures are supported.
+pub fn verify(token: &[u8], ca: &[u8], address: &U224) -> Result<String, Error> {
+    verify_with(token, &Certificate::parse(ca)?, address)
+}
+
+fn verify_with(token: &[u8], ca: &Certificate, address: &U224) -> Result<String, Error> {
+    let sd = signed_data(token)?;
+    let info = parse_tst_info(sd.tst_info)?;
+    if info.hashed_message != message_imprint(address) {
+        return Err("message imprint mismatch");
+    }
+    // version, sid, digestAlgorithm, signedAttrs, signatureAlgorithm, signature
+    let si = &sd.signer_info;
+    let sid = children(get(si, 1)?, SEQUENCE)?;
+    let attributes = get(si, 3)?;
+    check_signed_attributes(attributes, &digest(get(si, 2)?, sd.tst_info)?)?;
+    let signature = octet_string(get(si, 5)?)?;
+    let is_signer = |c: &Certificate| {
+        sid.len() == 2 && c.issuer.raw == sid[0].raw && c.serial.raw == sid[1].raw
+    };
+    let tsa;
+    let signer = if is_signer(ca) {
+        ca
+    } else {
+        tsa = sd
+            .certificates
+            .iter()
+            .map(|c| Certificate::parse(c.raw))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .find(is_signer)
+            .ok_or("missing TSA certificate")?;
+        if !tsa.is_issued_by(ca) {
+            return Err("untrusted certificate");
+        }
+        // the CA should be valid when the TSA certificate is used.
+        if !ca.is_valid_at(&info.gen_time) {
+            return Err("invalid CA certificate");
+        }
+        &tsa
+    };
+    if !signer.time_stamping || !signer.is_valid_at(&info.gen_time) {
+        return Err("invalid TSA certificate");
+    }
+    // signed attributes are signed as `SET OF`.
+    let signed = [[SET].as_slice(), &attributes.raw[1..]].concat();
+    if !signer.public_key.verify(get(si, 4)?, &signed, signature)? {
+        return Err("invalid time stamp signature");
+    }
+    Ok(info.gen_time)
+}
+
+#[cfg(test)]
+pub mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{
+        asn1::pem::from_pem,
+        common::{base32::StrEx, base64::from_base64_str},
+        uint::u224::U224,
+    };
+
+    use super::{cert::Certificate, request, signed_data, tst_info, verify, verify_with};
+
+    pub const ADDRESS: &str = "3v1d4j94scaseqgcyzr0ha5dxa9rx6ppnfbndck971ack";
+
+    /// A token for `ADDRESS` signed by an Ed25519 TSA certificate which is issued by `CA`.
+    pub const TOKEN: &str = concat!(
+        "MIIDaQYJKoZIhvcNAQcCoIIDWjCCA1YCAQMxDTALBglghkgBZQMEAgMwgYMGCyqGSIb3DQEJEAEE",
+        "oHQEcjBwAgEBBgQqAwQBMFEwDQYJYIZIAWUDBAIDBQAEQDg4PoYZC1yEvJfKGz8PHGmLdttmq/Zw",
+        "oY6rPzEajY0hLYltjiNBoQr34GkCuyNnu/M5H3w52EIxmwtOC/y7a/ACASoYDzIwMjYxMDE5MTIw",
+        "MDAwWqCCAVcwggFTMIIBBaADAgECAhRtXJXGF6Mot+raYC2CVnAfopr4DDAFBgMrZXAwEjEQMA4G",
+        "A1UEAwwHVGVzdCBDQTAgFw0yNjEwMTkwNTQ5NThaGA8yMTI2MDkyNTA1NDk1OFowEzERMA8GA1UE",
+        "AwwIVGVzdCBUU0EwKjAFBgMrZXADIQA0uW2eAZXG3fXmtUT2m0IMlrspGLmojuX3MfcCoYNRyKNq",
+        "MGgwFgYDVR0lAQH/BAwwCgYIKwYBBQUHAwgwDgYDVR0PAQH/BAQDAgeAMB0GA1UdDgQWBBRUQbe3",
+        "0vPOfP9TPCTWMkKv3sTQFjAfBgNVHSMEGDAWgBTFsN8bMPEDakTQrn0HMRKn8yGGmzAFBgMrZXAD",
+        "QQCIFJ2kxdsTMYAGqURf49jO0KgOqNAlLnWn5jdkQA+s04jMqGDsy8VKtDkkcTbSgd+RJeyRKZ/F",
+        "R0uuys76NHAAMYIBXzCCAVsCAQEwKjASMRAwDgYDVQQDDAdUZXN0IENBAhRtXJXGF6Mot+raYC2C",
+        "VnAfopr4DDALBglghkgBZQMEAgOggdMwGgYJKoZIhvcNAQkDMQ0GCyqGSIb3DQEJEAEEME8GCSqG",
+        "SIb3DQEJBDFCBECHt8V3tsPQ0ArpSINB8sJlUlahotAn851nTsiem2XV97jqIJH12GL+2KOUZgo8",
+        "gtNGLQNQjH01pflZ2qqwm9MdMGQGCyqGSIb3DQEJEAIvMVUwUzBRME8wCwYJYIZIAWUDBAIDBEDp",
+        "2YXfiVOxVMXZK3xNbS6NTTO+KCYquU3fCgKYirvp+FRS7bRfmsKb6nbbtzm38El5PGTFta52NE5J",
+        "hF3yhiQ5MAUGAytlcARArRZZZoXUU2SG/swlr1+fi4Nyj/VEkQOpInrpBtWKf2XWhgldZINngesV",
+        "rSQrSa2qaidnEbma9Chq68wiH2MYCg==",
+    );
+
+    pub const CA: &str = "-----BEGIN CERTIFICATE-----\nMIIBSjCB/aADAgECAhRdXa6ylVbxuNFgQyfLPl5KFDHMkDAFBgMrZXAwEjEQMA4G\nA1UEAwwHVGVzdCBDQTAgFw0yNjEwMTkwNTQ5NThaGA8yMTI2MDkyNTA1NDk1OFow\nEjEQMA4GA1UEAwwHVGVzdCBDQTAqMAUGAytlcAMhAPIdfFMUCeIyHmfmFrlVdTgb\nWVraQBllt+hPsAkLXuoSo2MwYTAdBgNVHQ4EFgQUxbDfGzDxA2pE0K59BzESp/Mh\nhpswHwYDVR0jBBgwFoAUxbDfGzDxA2pE0K59BzESp/MhhpswDwYDVR0TAQH/BAUw\nAwEB/zAOBgNVHQ8BAf8EBAMCAgQwBQYDK2VwA0EAzeVqJwQThzmarN6B1ymziCOk\nBMF0Ogjs548e/+P1zV/ag0hEeiX3hIQZ3O0b3rBJD/UZ0ePFHai5UdRAFm2rAg==\n-----END CERTIFICATE-----";
+
+    const OTHER_CA: &str = "-----BEGIN CERTIFICATE-----\nMIIBOjCB7aADAgECAhRYNKSlGYJYiMt/7KspZGJiGtISZzAFBgMrZXAwEjEQMA4G\nA1UEAwwHVGVzdCBDQTAgFw0yNjEwMTkwNTUwNTBaGA8yMTI2MDkyNTA1NTA1MFow\nEjEQMA4GA1UEAwwHVGVzdCBDQTAqMAUGAytlcAMhAKVeU0grcbXDEw2DaZhLShl9\n2/J3WkBCk10vhaPxz8kYo1MwUTAdBgNVHQ4EFgQUKixUs6rndtluY7SV0SO5zOpf\n+hIwHwYDVR0jBBgwFoAUKixUs6rndtluY7SV0SO5zOpf+hIwDwYDVR0TAQH/BAUw\nAwEB/zAFBgMrZXADQQBGUG3KrzKwE6TECIwIVl0KKU4TaRIP8yaY4/yjxdwPxof1\ngfTrG/bMd/WmbUDUGY0l2PJeLsUjiJsH0JKFXEEJ\n-----END CERTIFICATE-----";
+
+    /// `openssl ts -reply` with `signer_digest = sha256`. The P-256 TSA certificate is issued by
+    /// `RSA_CA`.
+    const EC_TOKEN: &str = concat!(
+        "MIIEJQYJKoZIhvcNAQcCoIIEFjCCBBICAQMxDzANBglghkgBZQMEAgEFADCBiAYLKoZIhvcNAQkQ",
+        "AQSgeQR3MHUCAQEGBCoDBAEwUTANBglghkgBZQMEAgMFAARAODg+hhkLXIS8l8obPw8caYt222ar",
+        "9nChjqs/MRqNjSEtiW2OI0GhCvfgaQK7I2e78zkffDnYQjGbC04L/Ltr8AIBKxgPMjAyNjEwMTkw",
+        "NzAzNDVaMAMCAQGgggJCMIICPjCCASagAwIBAgIBAzANBgkqhkiG9w0BAQsFADARMQ8wDQYDVQQD",
+        "DAZSU0EgQ0EwIBcNMjYxMDE5MDcwMzQ1WhgPMjEyNjA5MjUwNzAzNDVaMBExDzANBgNVBAMMBkVD",
+        "IFRTQTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABMf0OKhyjKOX0fSrdkndOtI0HN9mwAdJm2bM",
+        "z+SxRbndn0eYIP6k+76EM+N7Fvh7cBDMcw/NLmW8s4zextia4WujajBoMBYGA1UdJQEB/wQMMAoG",
+        "CCsGAQUFBwMIMA4GA1UdDwEB/wQEAwIHgDAdBgNVHQ4EFgQUB5PtTP4Uggcwbx8t/t3IZRqPSGIw",
+        "HwYDVR0jBBgwFoAUysi6uzVRA+x0yOR1GBcRsBJn+iwwDQYJKoZIhvcNAQELBQADggEBADWGD5T3",
+        "IGvilQPUM26akExpbblSS10nDfR+aZ4PqVzYglA1ncdT+9jEcq+DkWGhXO/7JKBgGlHrkxL8IWfS",
+        "B5QFhwFbxF1I16D13ORwxEvzwJOkIb2JMN5UcyocPk03GuLeRT4tiKrS7owA4eipJfva/6rdjiUC",
+        "yVAd4uqo3egAhM3t/KBas3/P3OyTm2kl1HuAY5qWDu+0KDOU2lCsVVvjTz0+VgaPz5QVOxr3Ggx+",
+        "3l9s+mAIkGNKDktpDiU35iNwA9OzxBhdDJiq7dsRP7uuChBrrV9kYYpooP0zdApQakR4xPhboky1",
+        "XDSm+jQTKMXJKT1g30OJ5W0+EJU1B8ExggEpMIIBJQIBATAWMBExDzANBgNVBAMMBlJTQSBDQQIB",
+        "AzANBglghkgBZQMEAgEFAKCBpDAaBgkqhkiG9w0BCQMxDQYLKoZIhvcNAQkQAQQwHAYJKoZIhvcN",
+        "AQkFMQ8XDTI2MTAxOTA3MDM0NVowLwYJKoZIhvcNAQkEMSIEIMiDbP3Es7NELHu7QGRVrh2W5z8s",
+        "U0i89l3fk78TYRs8MDcGCyqGSIb3DQEJEAIvMSgwJjAkMCIEIE5zWiNKQF26UYhfYb/jo4LNBOY2",
+        "73VPTLTXYb85YmSaMAoGCCqGSM49BAMCBEYwRAIgSS5gYKLnO/T/Ge97jMuTvHJdLvMKiWHj3LIV",
+        "jNQ8+H4CIDkNkG2CsoGnrOdTqC1/JyE2eofiHVPlYPNdIQwyMWam",
+    );
+
+    const RSA_CA: &str = "-----BEGIN CERTIFICATE-----\nMIIDFTCCAf2gAwIBAgIUFf9NyBeVBCfwUN6zAJqprUH3Vx0wDQYJKoZIhvcNAQEL\nBQAwETEPMA0GA1UEAwwGUlNBIENBMCAXDTI2MTAxOTA3MDM0NFoYDzIxMjYwOTI1\nMDcwMzQ0WjARMQ8wDQYDVQQDDAZSU0EgQ0EwggEiMA0GCSqGSIb3DQEBAQUAA4IB\nDwAwggEKAoIBAQCj0+fA458lj3/Q13jRNxdReumzFvDf9uAtqay9Co7tJvNJwF9s\nijJY7zV9roPhguVzT7oQIYAftegn1kFnolAO5oniDKs5SJFnq/oVPaNMdMMmCugE\nyktiAdHkZqEqRdMWUpGlLxOJDFCg/K/KrqrIlafPTYG5uGBnyglI6C4NnfG7SeMP\n/W5jyKKxhh09SRVwyNbF7zsI8z9qtwOfaIFKRQoNhnoP4EXUeswDd4VhAvPo/G2Y\nW6KchaJI36hww+DEzXxf5V1UKD76oMdmwzjZSfxzBVFkTV0fC460JYuv7HOqeXYr\neq5Opr78+BZ2ckeYcvtMpXJXwp10m2DmR4i3AgMBAAGjYzBhMB0GA1UdDgQWBBTK\nyLq7NVED7HTI5HUYFxGwEmf6LDAfBgNVHSMEGDAWgBTKyLq7NVED7HTI5HUYFxGw\nEmf6LDAPBgNVHRMBAf8EBTADAQH/MA4GA1UdDwEB/wQEAwICBDANBgkqhkiG9w0B\nAQsFAAOCAQEAFb4otkspC9SXFlIEyqGw/r7jRW0O8YN+5yE9Dc3gCjweO+8mJujt\n3YAj9po7tM2HdhUUZw2n7VrWQ7l1QdZrK47hiXDW03hrs0+r3RtofBDX5DAaAWNd\nkj1tOGeIO6m2hSX6ecm5hNz3VYiJOm0cfZ7WVcaYInVTtFCkF9LtAITAvDYHpEP1\nHKn7eYMMabI3ZlZGOHV/Yh1/deZoyT8UnRRd8JkOHKjmtAcU8oTya0kywUU7j4Nw\n/W6adxdD4myzcx/jQYOv8tsuj/jSXEkqoOw3XElXg89zVsivkQKhV7+jrw9YZo5l\npYCJexf4cLIaVgXZAigYm7VTTxBro3LLYQ==\n-----END CERTIFICATE-----";
+
+    /// The RSA TSA certificate is issued by `EC_CA`.
+    const RSA_TOKEN: &str = concat!(
+        "MIIE7gYJKoZIhvcNAQcCoIIE3zCCBNsCAQMxDzANBglghkgBZQMEAgEFADCBiAYLKoZIhvcNAQkQ",
+        "AQSgeQR3MHUCAQEGBCoDBAEwUTANBglghkgBZQMEAgMFAARAODg+hhkLXIS8l8obPw8caYt222ar",
+        "9nChjqs/MRqNjSEtiW2OI0GhCvfgaQK7I2e78zkffDnYQjGbC04L/Ltr8AIBLBgPMjAyNjEwMTkw",
+        "NzAzNDVaMAMCAQGgggJNMIICSTCCAe6gAwIBAgIBBDAKBggqhkjOPQQDAjAQMQ4wDAYDVQQDDAVF",
+        "QyBDQTAgFw0yNjEwMTkwNzAzNDVaGA8yMTI2MDkyNTA3MDM0NVowEjEQMA4GA1UEAwwHUlNBIFRT",
+        "QTCCASIwDQYJKoZIhvcNAQEBBQADggEPADCCAQoCggEBAPQwfGDO6QM7J85wXO1Yovvr4DV+fRPs",
+        "QyTKelRi07Cski5k6yppd7cUffQGWWVc2J3eLTiTeOrNw0aU9SWKZlFkaIvfgiovxGmgEnI6JWh1",
+        "PeCdkQFTM4N2N/jks/T+pMtV3kT1+wIMFaH15izHjPZhgI6k2nhePhOPh4atkfJOC4oj7K7Mv/kY",
+        "bOb38z6zKFLLVduKy8k0tkxzn1Laji0IfVwsqKf+wQWWZW+eRXZ+nr533UF2S2wbkWkM8fKyctmb",
+        "jxg9WeDuJs5jXbBgqdxOaJhorDzTgf2fogeoTPZD4J1LGY4O+ehZd//rD9CKvKrUqjVK7AiiAWG6",
+        "F2AvtfUCAwEAAaNqMGgwFgYDVR0lAQH/BAwwCgYIKwYBBQUHAwgwDgYDVR0PAQH/BAQDAgeAMB0G",
+        "A1UdDgQWBBR7lFLZ0oE9fhE03hE825TaWkaF+jAfBgNVHSMEGDAWgBRkqjrN3ltVkoNwoWJOQRXf",
+        "FBXz8zAKBggqhkjOPQQDAgNJADBGAiEA8whQDXM3GQ7cLeZ7V6w4bZiYVMITZxJHgnfcbws0iJEC",
+        "IQC/dpmhtH97Gs6+fC3xL7q4cjGqpgZu6V6H8gXZJjoyZzGCAecwggHjAgEBMBUwEDEOMAwGA1UE",
+        "AwwFRUMgQ0ECAQQwDQYJYIZIAWUDBAIBBQCggaQwGgYJKoZIhvcNAQkDMQ0GCyqGSIb3DQEJEAEE",
+        "MBwGCSqGSIb3DQEJBTEPFw0yNjEwMTkwNzAzNDVaMC8GCSqGSIb3DQEJBDEiBCBClTEd4P8hUl5v",
+        "JldGs3ziJmkqy0KdISbc84AXZrw+7TA3BgsqhkiG9w0BCRACLzEoMCYwJDAiBCCaMJqCj5S88ZgT",
+        "TJEjWOwBWSEw0tb9dhlW3FzUxEuuTDANBgkqhkiG9w0BAQEFAASCAQBVwPeLQVMkUOWRqVI8L+uV",
+        "0RSrTtBJUlTDqeJnlnBObS3Jx82hs7geevWWub2dprfOnwfKbA9JGBCPDqdB23WsjS4XPgC9R/4O",
+        "OCJa1xn++Nk/4Kc5jziGb+NRU7YTHPsosmSYFpg0RaGXJywzBJONxZARKJ/VTF8RbkH5eEhlZkvq",
+        "5FumZEvHVOV+q35ByZqKrVy+sOt7cPhPd+/Uef1gjB+spWxzJ1h+ZVRGlf5SDQG1rhIYGWHskT7a",
+        "DwFbWOerg0d2YwVeXxZoRN4k8UcB1EmqXC+HzqdqsAJOogWWSW8MioV2nOja91YHod/oyEV1FU+l",
+        "4wOzmrjJTQ2Nf224",
+    );
+
+    const EC_CA: &str = "-----BEGIN CERTIFICATE-----\nMIIBhjCCAS2gAwIBAgIURUVZal2mlf/Pcm5y7oYbYHz89hwwCgYIKoZIzj0EAwIw\nEDEOMAwGA1UEAwwFRUMgQ0EwIBcNMjYxMDE5MDcwMzQ0WhgPMjEyNjA5MjUwNzAz\nNDRaMBAxDjAMBgNVBAMMBUVDIENBMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE\n8PSn3GiU8oTMpjKQmu6lLHEuBOVt7vAjAS1jV7jEtZ36T/GmEC/XKkyN5Jdncuru\nHLJnYlKARjAKqZaOiW2DdqNjMGEwHQYDVR0OBBYEFGSqOs3eW1WSg3ChYk5BFd8U\nFfPzMB8GA1UdIwQYMBaAFGSqOs3eW1WSg3ChYk5BFd8UFfPzMA8GA1UdEwEB/wQF\nMAMBAf8wDgYDVR0PAQH/BAQDAgIEMAoGCCqGSM49BAMCA0cAMEQCICw1YgvHwV4D\nsUIiz24T7OroEXIo57jtPXwVT2wyy+dxAiAE3hcsRJ88X+ItK7AWSf0muy7o3kLl\nN6DdFR3fULR6gw==\n-----END CERTIFICATE-----";
+
+    /// Issued by the TSA certificate of `EC_TOKEN` which is not a CA.
+    const LEAF: &str = "-----BEGIN CERTIFICATE-----\nMIIBYzCCAQmgAwIBAgIBBTAKBggqhkjOPQQDAjARMQ8wDQYDVQQDDAZFQyBUU0Ew\nIBcNMjYxMDE5MDcwMzQ1WhgPMjEyNjA5MjUwNzAzNDVaMA8xDTALBgNVBAMMBExl\nYWYwWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAS8CFqXPPW/LElN+1OOsn2EQCxf\nf3ATqBqxc+JMzlWpW/fTsxjF0Uw+lQ74CIyz4bb4zF3m8Q9y3Z7Rpr4HNPx3o1Iw\nUDAOBgNVHQ8BAf8EBAMCB4AwHQYDVR0OBBYEFO6ZcSd++Ilyu5p4Kpv4ayTm12GB\nMB8GA1UdIwQYMBaAFAeT7Uz+FIIHMG8fLf7dyGUaj0hiMAoGCCqGSM49BAMCA0gA\nMEUCIFC4hP458LqUQcpjtxqzyjWXf6gu85+JC0zJzyTYVhMCAiEAsdbC4rOvFL1U\nGhi63t1InwJOt4DmQQeZvScvNI6diuc=\n-----END CERTIFICATE-----";
+
+    fn address() -> U224 {
+        ADDRESS.from_base32().unwrap()
+    }
+
+    fn token() -> Vec<u8> {
+        from_base64_str(TOKEN).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_request() {
+        // openssl ts -query -sha512 -cert -no_nonce
+        assert_eq!(
+            request(&address()).unwrap(),
+            from_base64_str(concat!(
+                "MFkCAQEwUTANBglghkgBZQMEAgMFAARAODg+hhkLXIS8l8obPw8caYt222ar9nChjqs/MRqNjSEtiW2O",
+                "I0GhCvfgaQK7I2e78zkffDnYQjGbC04L/Ltr8AEB/w=="
+            ))
+            .unwrap()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_verify() {
+        let ca = from_pem(CA).unwrap();
+        let t = token();
+        assert_eq!(tst_info(&t).unwrap().gen_time, "20261019120000Z");
+        assert_eq!(
+            verify(&t, &ca, &address()),
+            Ok("20261019120000Z".to_owned())
+        );
+        assert_eq!(
+            verify(&t, &from_pem(OTHER_CA).unwrap(), &address()),
+            Err("untrusted certificate")
+        );
+        assert_eq!(verify(&t, &ca, &[0; 7]), Err("message imprint mismatch"));
+        assert_eq!(
+            verify(&t[1..], &ca, &address()),
+            Err("invalid time stamp token")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_tampered() {
+        let ca = from_pem(CA).unwrap();
+        let mut t = token();
+        // change `genTime` in `TSTInfo`.
+        let i = t.windows(4).position(|w| w == b"2026").unwrap();
+        t[i + 3] = b'7';
+        assert_eq!(verify(&t, &ca, &address()), Err("message digest mismatch"));
+        // change the signature.
+        let mut t = token();
+        let n = t.len();
+        t[n - 1] ^= 1;
+        assert_eq!(
+            verify(&t, &ca, &address()),
+            Err("invalid time stamp signature")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_verify_rsa_and_ecdsa() {
+        let rsa_ca = from_pem(RSA_CA).unwrap();
+        let ec_ca = from_pem(EC_CA).unwrap();
+        let ec_token = from_base64_str(EC_TOKEN).unwrap();
+        let rsa_token = from_base64_str(RSA_TOKEN).unwrap();
+        let time = Ok("20261019070345Z".to_owned());
+        assert_eq!(verify(&ec_token, &rsa_ca, &address()), time);
+        assert_eq!(verify(&rsa_token, &ec_ca, &address()), time);
+        assert_eq!(
+            verify(&ec_token, &ec_ca, &address()),
+            Err("untrusted certificate")
+        );
+        // change the signatures.
+        for (mut t, ca) in [(ec_token, rsa_ca), (rsa_token, ec_ca)] {
+            let n = t.len();
+            t[n - 2] ^= 1;
+            assert_eq!(
+                verify(&t, &ca, &address()),
+                Err("invalid time stamp signature")
+            );
+        }
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_ca_constraints() {
+        let token = from_base64_str(EC_TOKEN).unwrap();
+        let sd = signed_data(&token).unwrap();
+        let mut tsa = Certificate::parse(sd.certificates[0].raw).unwrap();
+        let leaf = from_pem(LEAF).unwrap();
+        let leaf = Certificate::parse(&leaf).unwrap();
+        assert!(tsa.time_stamping && !tsa.is_ca && !tsa.key_cert_sign);
+        assert!(!leaf.is_issued_by(&tsa));
+        tsa.is_ca = true;
+        assert!(!leaf.is_issued_by(&tsa));
+        tsa.key_cert_sign = true;
+        assert!(leaf.is_issued_by(&tsa));
+        let ca = from_pem(RSA_CA).unwrap();
+        let ca = Certificate::parse(&ca).unwrap();
+        assert!(ca.is_ca && ca.key_cert_sign && !ca.time_stamping);
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_ca_validity() {
+        let token = from_base64_str(EC_TOKEN).unwrap();
+        let ca = from_pem(RSA_CA).unwrap();
+        let mut ca = Certificate::parse(&ca).unwrap();
+        let time = Ok("20261019070345Z".to_owned());
+        assert_eq!(verify_with(&token, &ca, &address()), time);
+        ca.not_after = "20261019070344Z".to_owned();
+        assert_eq!(
+            verify_with(&token, &ca, &address()),
+            Err("invalid CA certificate")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_critical_extension() {
+        let mut leaf = from_pem(LEAF).unwrap();
+        // `KeyUsage` is critical, replace it with `CertificatePolicies`.
+        let key_usage = [6, 3, 0x55, 0x1d, 0x0f, 1, 1, 0xff];
+        let i = leaf.windows(8).position(|w| w == key_usage).unwrap();
+        leaf[i + 4] = 0x20;
+        assert_eq!(
+            Certificate::parse(&leaf).err(),
+            Some("unsupported critical extension")
+        );
+    }
+}
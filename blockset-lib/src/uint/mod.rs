@@ -1,3 +1,4 @@
+pub mod mont;
 pub mod u128;
 pub mod u224;
 pub mod u256;
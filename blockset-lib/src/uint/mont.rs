@@ -0,0 +1,220 @@
+//! Arithmetic modulo an odd number in the Montgomery form. Numbers are little-endian 64-bit limbs
+//! of the same length as the modulus. It's for signature verification, so it's not constant time.
+
+pub type Limbs = Vec<u64>;
+
+/// Big-endian bytes to `len` limbs. `None` if the number doesn't fit.
+pub fn from_be_bytes(v: &[u8], len: usize) -> Option<Limbs> {
+    let v = &v[v.iter().take_while(|&&b| b == 0).count()..];
+    if v.len() > len * 8 {
+        return None;
+    }
+    let mut result = [0].repeat(len);
+    for (i, &b) in v.iter().rev().enumerate() {
+        result[i / 8] |= (b as u64) << (i % 8 * 8);
+    }
+    Some(result)
+}
+
+/// Limbs to `len` big-endian bytes. Higher bytes are dropped.
+pub fn to_be_bytes(a: &[u64], len: usize) -> Vec<u8> {
+    (0..len)
+        .rev()
+        .map(|i| a.get(i / 8).map_or(0, |x| (x >> (i % 8 * 8)) as u8))
+        .collect()
+}
+
+pub fn is_zero(a: &[u64]) -> bool {
+    a.iter().all(|&x| x == 0)
+}
+
+/// Missing high limbs are zeros.
+pub fn less(a: &[u64], b: &[u64]) -> bool {
+    let limb = |v: &[u64], i| v.get(i).copied().unwrap_or(0);
+    (0..a.len().max(b.len()))
+        .rev()
+        .map(|i| limb(a, i).cmp(&limb(b, i)))
+        .find(|o| o.is_ne())
+        .is_some_and(|o| o.is_lt())
+}
+
+/// `a + b`, returns the carry.
+fn add_to(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (s, c0) = x.overflowing_add(y);
+        let (s, c1) = s.overflowing_add(carry as u64);
+        *x = s;
+        carry = c0 || c1;
+    }
+    carry
+}
+
+/// `a - b`, returns the borrow.
+fn sub_from(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (d, b0) = x.overflowing_sub(y);
+        let (d, b1) = d.overflowing_sub(borrow as u64);
+        *x = d;
+        borrow = b0 || b1;
+    }
+    borrow
+}
+
+pub struct Mont {
+    n: Limbs,
+    /// `-n^-1 mod 2^64`
+    n0: u64,
+    /// `R^2 mod n` where `R = 2^(64 * n.len())`.
+    r2: Limbs,
+}
+
+impl Mont {
+    /// `None` if the modulus is even or less than 3.
+    pub fn new(n: Limbs) -> Option<Self> {
+        if n.is_empty() || n[0] & 1 == 0 || less(&n, &[3]) {
+            return None;
+        }
+        // Newton's iteration doubles the number of correct low bits.
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+        let mut r2 = [0].repeat(n.len());
+        r2[0] = 1;
+        for _ in 0..n.len() * 128 {
+            let carry = r2[r2.len() - 1] >> 63 != 0;
+            for i in (0..r2.len()).rev() {
+                r2[i] = (r2[i] << 1) | if i > 0 { r2[i - 1] >> 63 } else { 0 };
+            }
+            if carry || !less(&r2, &n) {
+                sub_from(&mut r2, &n);
+            }
+        }
+        Some(Self {
+            n,
+            n0: inv.wrapping_neg(),
+            r2,
+        })
+    }
+    pub fn len(&self) -> usize {
+        self.n.len()
+    }
+    pub fn modulus(&self) -> &[u64] {
+        &self.n
+    }
+    pub fn add(&self, a: &[u64], b: &[u64]) -> Limbs {
+        let mut result = a.to_vec();
+        if add_to(&mut result, b) || !less(&result, &self.n) {
+            sub_from(&mut result, &self.n);
+        }
+        result
+    }
+    pub fn sub(&self, a: &[u64], b: &[u64]) -> Limbs {
+        let mut result = a.to_vec();
+        if sub_from(&mut result, b) {
+            add_to(&mut result, &self.n);
+        }
+        result
+    }
+    /// `a * b / R mod n` for `a, b < n`.
+    pub fn mul(&self, a: &[u64], b: &[u64]) -> Limbs {
+        let s = self.n.len();
+        let mut t = [0u64].repeat(s + 2);
+        for &bi in b {
+            let mut c = 0u128;
+            for j in 0..s {
+                c += t[j] as u128 + a[j] as u128 * bi as u128;
+                t[j] = c as u64;
+                c >>= 64;
+            }
+            c += t[s] as u128;
+            t[s] = c as u64;
+            t[s + 1] = (c >> 64) as u64;
+            let m = t[0].wrapping_mul(self.n0);
+            let mut c = (t[0] as u128 + m as u128 * self.n[0] as u128) >> 64;
+            for j in 1..s {
+                c += t[j] as u128 + m as u128 * self.n[j] as u128;
+                t[j - 1] = c as u64;
+                c >>= 64;
+            }
+            c += t[s] as u128;
+            t[s - 1] = c as u64;
+            t[s] = t[s + 1] + (c >> 64) as u64;
+        }
+        let mut result = t[..s].to_vec();
+        if t[s] != 0 || !less(&result, &self.n) {
+            sub_from(&mut result, &self.n);
+        }
+        result
+    }
+    /// To the Montgomery form, `a < n`.
+    pub fn to_mont(&self, a: &[u64]) -> Limbs {
+        self.mul(a, &self.r2)
+    }
+    pub fn to_normal(&self, a: &[u64]) -> Limbs {
+        let mut one = [0].repeat(self.n.len());
+        one[0] = 1;
+        self.mul(a, &one)
+    }
+    /// `1` in the Montgomery form.
+    pub fn one(&self) -> Limbs {
+        let mut one = [0].repeat(self.n.len());
+        one[0] = 1;
+        self.to_mont(&one)
+    }
+    /// `a^e` in the Montgomery form.
+    pub fn pow(&self, a: &[u64], e: &[u64]) -> Limbs {
+        let mut result = self.one();
+        for i in (0..e.len() * 64).rev() {
+            result = self.mul(&result, &result);
+            if (e[i / 64] >> (i % 64)) & 1 != 0 {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{from_be_bytes, less, to_be_bytes, Mont};
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_bytes() {
+        let a = from_be_bytes(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9], 2).unwrap();
+        assert_eq!(a, [0x0203_0405_0607_0809, 1]);
+        assert_eq!(to_be_bytes(&a, 10), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(from_be_bytes(&[1; 9], 1), None);
+        assert!(less(&[5, 1], &[4, 2]));
+        assert!(!less(&[5, 2], &[4, 2]));
+        assert!(less(&[2], &[0, 1]) && !less(&[3, 0], &[3]));
+    }
+
+    #[wasm_bindgen_test]
+    #[test]
+    fn test_mont() {
+        assert!(Mont::new([10].to_vec()).is_none());
+        assert!(Mont::new([1].to_vec()).is_none());
+        // 2^127 - 1 is a prime.
+        let p = [u64::MAX, u64::MAX >> 1];
+        let m = Mont::new(p.to_vec()).unwrap();
+        let a = [123456789, 987654321];
+        let b = [u64::MAX - 7, 12345];
+        let (am, bm) = (m.to_mont(&a), m.to_mont(&b));
+        // the Fermat's little theorem.
+        let e = [u64::MAX - 1, u64::MAX >> 1];
+        assert_eq!(m.to_normal(&m.pow(&am, &e)), [1, 0]);
+        let ab = m.to_normal(&m.mul(&am, &bm));
+        // `a * b mod p` in Python.
+        let expected = 0x0161_0a4d_213a_0000_162d_d92b_678c_u128;
+        assert_eq!(ab, [expected as u64, (expected >> 64) as u64]);
+        assert_eq!(m.to_normal(&m.sub(&am, &am)), [0, 0]);
+        assert_eq!(m.add(&[u64::MAX - 1, u64::MAX >> 1], &[1, 0]), [0, 0]);
+        assert_eq!(m.sub(&[0, 0], &[1, 0]), [u64::MAX - 1, u64::MAX >> 1]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::uint::u128::{shl as shl128, to_u32x4, u32x4_add};
+use crate::uint::u128::{from_u32x4, shl as shl128, to_u32x4, u32x4_add};
 
 pub type U256 = [u128; 2];
 
@@ -35,6 +35,14 @@ pub const fn great(a: &U256, b: &U256) -> bool {
     less(b, a)
 }
 
+/// A node id of a hash, the inverse of `to_u224`.
+pub const fn from_u224(&[a0, a1, a2, a3, a4, a5, a6]: &[u32; 7]) -> U256 {
+    [
+        from_u32x4([a0, a1, a2, a3]),
+        from_u32x4([a4, a5, a6, 0xFFFF_FFFF]),
+    ]
+}
+
 pub const fn to_u224(&[a0, a1]: &U256) -> Option<[u32; 7]> {
     let [a10, a11, a12, a13] = to_u32x4(a1);
     if a13 != 0xFFFF_FFFF {
@@ -1,8 +1,158 @@
-use std::io;
+use std::{
+    io::{self, IsTerminal, Write},
+    process::exit,
+};
 
-use blockset_lib::run;
+use blockset_lib::{exit_code, run, Host};
 use io_impl::RealIo;
+use io_trait::Io;
 
-fn main() -> io::Result<()> {
-    run(&RealIo::default())
+/// `RealIo` with platform services which `Io` doesn't have.
+#[derive(Default)]
+struct Process(RealIo);
+
+impl Io for Process {
+    type Args = <RealIo as Io>::Args;
+    type File = <RealIo as Io>::File;
+    type Stdout = <RealIo as Io>::Stdout;
+    type Metadata = <RealIo as Io>::Metadata;
+    type DirEntry = <RealIo as Io>::DirEntry;
+    type Instant = <RealIo as Io>::Instant;
+    fn args(&self) -> Self::Args {
+        self.0.args()
+    }
+    fn stdout(&self) -> Self::Stdout {
+        self.0.stdout()
+    }
+    fn metadata(&self, path: &str) -> io::Result<Self::Metadata> {
+        self.0.metadata(path)
+    }
+    fn create_dir(&self, path: &str) -> io::Result<()> {
+        self.0.create_dir(path)
+    }
+    fn create(&self, path: &str) -> io::Result<Self::File> {
+        self.0.create(path)
+    }
+    fn open(&self, path: &str) -> io::Result<Self::File> {
+        self.0.open(path)
+    }
+    fn now(&self) -> Self::Instant {
+        self.0.now()
+    }
+    fn read_dir(&self, path: &str) -> io::Result<Vec<Self::DirEntry>> {
+        self.0.read_dir(path)
+    }
+    fn current_dir(&self) -> io::Result<String> {
+        self.0.current_dir()
+    }
+}
+
+impl Host for Process {
+    type Stderr = io::Stderr;
+    fn stderr(&self) -> io::Stderr {
+        io::stderr()
+    }
+    fn is_stderr_terminal(&self) -> bool {
+        io::stderr().is_terminal()
+    }
+    fn random(&self, buf: &mut [u8]) -> io::Result<()> {
+        random::fill(buf)
+    }
+    #[cfg(unix)]
+    fn set_owner_only(&self, path: &str) -> io::Result<()> {
+        use std::{
+            fs::{set_permissions, Permissions},
+            os::unix::fs::PermissionsExt,
+        };
+        set_permissions(path, Permissions::from_mode(0o600))
+    }
+    fn remove_file(&self, path: &str) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+}
+
+#[cfg(unix)]
+mod random {
+    use std::{
+        fs::File,
+        io::{self, Read},
+    };
+
+    pub fn fill(buf: &mut [u8]) -> io::Result<()> {
+        File::open("/dev/urandom")?.read_exact(buf)
+    }
+}
+
+#[cfg(windows)]
+mod random {
+    use std::{ffi::c_void, io, ptr::null_mut};
+
+    const BCRYPT_USE_SYSTEM_PREFERRED_RNG: u32 = 2;
+
+    #[link(name = "bcrypt")]
+    extern "system" {
+        fn BCryptGenRandom(algorithm: *mut c_void, buf: *mut u8, len: u32, flags: u32) -> i32;
+    }
+
+    pub fn fill(buf: &mut [u8]) -> io::Result<()> {
+        for chunk in buf.chunks_mut(u32::MAX as usize) {
+            // SAFETY: the pointer and the length describe the chunk, and no algorithm handle is
+            // needed with `BCRYPT_USE_SYSTEM_PREFERRED_RNG`.
+            let status = unsafe {
+                BCryptGenRandom(
+                    null_mut(),
+                    chunk.as_mut_ptr(),
+                    chunk.len() as u32,
+                    BCRYPT_USE_SYSTEM_PREFERRED_RNG,
+                )
+            };
+            if status != 0 {
+                return Err(io::Error::other("BCryptGenRandom failed"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "wasi")]
+mod random {
+    use std::io;
+
+    #[link(wasm_import_module = "wasi_snapshot_preview1")]
+    extern "C" {
+        fn random_get(buf: *mut u8, len: usize) -> i32;
+    }
+
+    pub fn fill(buf: &mut [u8]) -> io::Result<()> {
+        // SAFETY: the pointer and the length describe the buffer.
+        match unsafe { random_get(buf.as_mut_ptr(), buf.len()) } {
+            0 => Ok(()),
+            errno => Err(io::Error::other(
+                "random_get failed: ".to_owned() + &errno.to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows, target_os = "wasi")))]
+mod random {
+    use std::io;
+
+    pub fn fill(_: &mut [u8]) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no entropy source on this platform",
+        ))
+    }
+}
+
+fn main() {
+    let process = Process::default();
+    if let Err(e) = run(&process) {
+        // nothing else can be reported if stderr fails.
+        let _ = process
+            .stderr()
+            .write_all(("error: ".to_owned() + &e.to_string() + "\n").as_bytes());
+        exit(exit_code(&e));
+    }
 }
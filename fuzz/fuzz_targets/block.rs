@@ -0,0 +1,8 @@
+#![no_main]
+
+use blockset_lib::Block;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Block::parse(data);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use blockset_lib::parse_dir_block;
+use io_test::VirtualIo;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let io = VirtualIo::new(&[]);
+    let _ = parse_dir_block(&io, &Default::default(), data.to_vec());
+});